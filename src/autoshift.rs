@@ -0,0 +1,113 @@
+//! Delayed Auto Shift and Auto Repeat Rate.
+//!
+//! Turns held direction keys into horizontal moves. It only sees key states
+//! and elapsed time, so a frontend or a bot can drive it tick by tick.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+/// Cells to shift the piece this tick. `u32::MAX` means "as far as it goes".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shift {
+    pub direction: Direction,
+    pub cells: u32,
+}
+
+/// Delayed Auto Shift and Auto Repeat Rate for horizontal movement.
+///
+/// The most recently pressed direction wins while both keys are held.
+/// The charge keeps building while the engine is busy (e.g. during the
+/// line clear animation), so a held direction carries over to the next piece.
+pub struct AutoShift {
+    pub das: f32,
+    pub arr: f32,
+    direction: Option<Direction>,
+    held_time: f32,
+    repeat_timer: f32,
+}
+
+impl AutoShift {
+    pub fn new(das: f32, arr: f32) -> Self {
+        AutoShift {
+            das,
+            arr,
+            direction: None,
+            held_time: 0.0,
+            repeat_timer: 0.0,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        left_down: bool,
+        right_down: bool,
+        left_pressed: bool,
+        right_pressed: bool,
+        delta_time: f32,
+    ) -> Option<Shift> {
+        // A fresh press always takes over and moves one cell right away
+        let pressed = if right_pressed {
+            Some(Direction::Right)
+        } else if left_pressed {
+            Some(Direction::Left)
+        } else {
+            None
+        };
+        if let Some(direction) = pressed {
+            self.start(Some(direction));
+            return Some(Shift { direction, cells: 1 });
+        }
+
+        let is_down = |direction| match direction {
+            Direction::Left => left_down,
+            Direction::Right => right_down,
+        };
+
+        // Released the active key: fall back to the other one if it is still held
+        match self.direction {
+            Some(direction) if is_down(direction) => {}
+            Some(Direction::Left) if right_down => self.start(Some(Direction::Right)),
+            Some(Direction::Right) if left_down => self.start(Some(Direction::Left)),
+            _ => self.start(None),
+        }
+
+        let direction = self.direction?;
+        let was_charged = self.held_time >= self.das;
+        self.held_time += delta_time;
+        if self.held_time < self.das {
+            return None;
+        }
+
+        if self.arr <= 0.0 {
+            return Some(Shift { direction, cells: u32::MAX });
+        }
+
+        // The first shift happens as soon as DAS is charged, then every ARR
+        let mut cells = 0;
+        if was_charged {
+            self.repeat_timer += delta_time;
+        } else {
+            cells += 1;
+            self.repeat_timer = self.held_time - self.das;
+        }
+        while self.repeat_timer >= self.arr {
+            self.repeat_timer -= self.arr;
+            cells += 1;
+        }
+
+        if cells == 0 {
+            None
+        } else {
+            Some(Shift { direction, cells })
+        }
+    }
+
+    fn start(&mut self, direction: Option<Direction>) {
+        self.direction = direction;
+        self.held_time = 0.0;
+        self.repeat_timer = 0.0;
+    }
+}
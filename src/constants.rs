@@ -1,50 +1,63 @@
-use macroquad::prelude::*;
-
-// Grid dimensions
-pub const GRID_WIDTH: usize = 10;
-pub const GRID_HEIGHT: usize = 20;
-pub const BLOCK_SIZE: f32 = 30.0;
-
-// Screen dimensions
-pub const SCREEN_WIDTH: f32 = 800.0;
-pub const SCREEN_HEIGHT: f32 = 650.0;
-
-// Game grid offset (to center it on screen)
-pub const GRID_OFFSET_X: f32 = 50.0;
-pub const GRID_OFFSET_Y: f32 = 50.0;
-
-// UI panel position
-pub const UI_OFFSET_X: f32 = GRID_OFFSET_X + (GRID_WIDTH as f32 * BLOCK_SIZE) + 50.0;
-pub const UI_OFFSET_Y: f32 = 50.0;
+// Grid dimensions (the board size is a per-game rule within these bounds)
+pub const DEFAULT_GRID_WIDTH: usize = 10;
+pub const DEFAULT_GRID_HEIGHT: usize = 20; // visible rows
+pub const MIN_GRID_WIDTH: usize = 4;
+pub const MAX_GRID_WIDTH: usize = 20;
+pub const MIN_GRID_HEIGHT: usize = 4;
+pub const MAX_GRID_HEIGHT: usize = 40;
+pub const BUFFER_HEIGHT: usize = 20; // hidden vanish zone above the visible rows
+
+// Pieces spawn in rows 21-22, just above the visible field
+pub const SPAWN_Y: i32 = BUFFER_HEIGHT as i32 - 2;
+
+// Next queue
+pub const DEFAULT_PREVIEW_COUNT: usize = 5;
+pub const MAX_PREVIEW_COUNT: usize = 6;
 
 // Game timing
-pub const INITIAL_FALL_SPEED: f32 = 1.0; // seconds per row
-pub const FAST_DROP_SPEED: f32 = 0.05; // seconds per row when holding down
+pub const FRAMES_PER_SECOND: f32 = 60.0; // gravity is measured in rows per frame
+pub const TICK_DURATION: f32 = 1.0 / FRAMES_PER_SECOND; // seconds of play per simulation tick
+pub const MAX_GRAVITY: f32 = 20.0; // 20G: the piece reaches the floor instantly
+pub const SOFT_DROP_FACTOR: f32 = 20.0; // soft drop speed relative to gravity
 pub const LOCK_DELAY: f32 = 0.5; // seconds before piece locks
+pub const MAX_LOCK_RESETS: u32 = 15; // lock delay resets per piece in move-reset mode
 pub const LINE_CLEAR_ANIMATION_DURATION: f32 = 0.3; // seconds for line clear animation
 
+// Marathon
+pub const MAX_START_LEVEL: u32 = 20; // 20G from level 19 on
+pub const LINES_PER_LEVEL: u32 = 10; // fixed goal
+pub const GOAL_POINTS_PER_LEVEL: u32 = 5; // variable goal: level N needs 5 x N goal points
+
+// Sprint
+pub const SPRINT_LINES: u32 = 40; // lines to clear to finish
+pub const SPRINT_SPLIT_LINES: u32 = 10; // lines between split times
+
+// Ultra
+pub const ULTRA_TICKS: u64 = 120 * 60; // two minutes of simulation ticks
+
 // Scoring (Official Tetris Guidelines)
 pub const SCORE_SINGLE: u32 = 100;
 pub const SCORE_DOUBLE: u32 = 300;
 pub const SCORE_TRIPLE: u32 = 500;
 pub const SCORE_TETRIS: u32 = 800;
+// Each line past four in one clear, for piece sets taller than four cells
+pub const SCORE_EXTRA_LINE: u32 = 400;
 pub const SCORE_SOFT_DROP: u32 = 1;  // Points per cell (soft drop)
 pub const SCORE_HARD_DROP: u32 = 2;  // Points per cell (hard drop)
-
-// Colors
-pub const COLOR_BACKGROUND: Color = Color::new(0.1, 0.1, 0.12, 1.0);
-pub const COLOR_GRID: Color = Color::new(0.2, 0.2, 0.25, 1.0);
-pub const COLOR_TEXT: Color = Color::new(0.9, 0.9, 0.9, 1.0);
-pub const COLOR_GHOST: f32 = 0.3; // Alpha value for ghost piece
-
-// Tetromino colors
-pub const COLOR_I: Color = Color::new(0.0, 0.9, 0.9, 1.0);    // Cyan
-pub const COLOR_O: Color = Color::new(0.9, 0.9, 0.0, 1.0);    // Yellow
-pub const COLOR_T: Color = Color::new(0.7, 0.0, 0.9, 1.0);    // Purple
-pub const COLOR_S: Color = Color::new(0.0, 0.9, 0.0, 1.0);    // Green
-pub const COLOR_Z: Color = Color::new(0.9, 0.0, 0.0, 1.0);    // Red
-pub const COLOR_J: Color = Color::new(0.0, 0.0, 0.9, 1.0);    // Blue
-pub const COLOR_L: Color = Color::new(0.9, 0.5, 0.0, 1.0);    // Orange
-
-// High score file
-pub const HIGHSCORE_FILE: &str = "highscore.txt";
+pub const SCORE_COMBO: u32 = 50;     // Points per combo step (multiplied by level)
+
+// Perfect clear bonus, on top of the line clear score (multiplied by level)
+pub const SCORE_PERFECT_CLEAR_SINGLE: u32 = 800;
+pub const SCORE_PERFECT_CLEAR_DOUBLE: u32 = 1200;
+pub const SCORE_PERFECT_CLEAR_TRIPLE: u32 = 1800;
+pub const SCORE_PERFECT_CLEAR_TETRIS: u32 = 2000;
+pub const SCORE_PERFECT_CLEAR_B2B_TETRIS: u32 = 3200;
+
+// T-spin scoring (multiplied by level)
+pub const SCORE_TSPIN_MINI: u32 = 100;
+pub const SCORE_TSPIN_MINI_SINGLE: u32 = 200;
+pub const SCORE_TSPIN_MINI_DOUBLE: u32 = 400;
+pub const SCORE_TSPIN: u32 = 400;
+pub const SCORE_TSPIN_SINGLE: u32 = 800;
+pub const SCORE_TSPIN_DOUBLE: u32 = 1200;
+pub const SCORE_TSPIN_TRIPLE: u32 = 1600;
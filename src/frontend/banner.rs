@@ -0,0 +1,46 @@
+use macroquad::prelude::*;
+use super::constants::*;
+use super::layout::Layout;
+
+/// Short-lived message drawn over the board, fading out as it expires.
+pub struct Banner {
+    text: String,
+    timer: f32,
+}
+
+impl Banner {
+    pub fn new() -> Self {
+        Banner {
+            text: String::new(),
+            timer: 0.0,
+        }
+    }
+
+    pub fn show(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.timer = BANNER_DURATION;
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.timer = (self.timer - delta_time).max(0.0);
+    }
+
+    pub fn draw(&self, layout: &Layout) {
+        if self.timer <= 0.0 {
+            return;
+        }
+
+        let mut color = COLOR_BANNER;
+        color.a = (self.timer / BANNER_DURATION).min(1.0);
+
+        let text_size = 40.0;
+        let text_dims = measure_text(&self.text, None, text_size as u16, 1.0);
+        draw_text(
+            &self.text,
+            layout.grid_x + (layout.grid_width - text_dims.width) / 2.0,
+            layout.grid_y + layout.grid_height / 3.0,
+            text_size,
+            color,
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use macroquad::prelude::*;
+
+pub const MAX_BLOCK_SIZE: f32 = 30.0; // blocks shrink below this to fit large boards
+
+// Screen dimensions
+pub const SCREEN_WIDTH: f32 = 800.0;
+pub const SCREEN_HEIGHT: f32 = 650.0;
+
+// Game grid offset (to center it on screen)
+pub const GRID_OFFSET_X: f32 = 50.0;
+pub const GRID_OFFSET_Y: f32 = 50.0;
+
+// Longest frame the simulation catches up on; slower frames run the game in slow motion
+pub const MAX_FRAME_TIME: f32 = 0.25;
+// A frame this long means the window stopped drawing (minimized, hidden, dragged), so the game pauses
+pub const AUTO_PAUSE_FRAME_TIME: f32 = 0.5;
+// How often the window manager is asked whether the window still has focus
+pub const FOCUS_POLL_TIME: f32 = 0.1;
+// X11 window class, which is how the focus check recognizes the game's window
+pub const WM_CLASS: &str = "tetris";
+
+// Replay viewer
+pub const REPLAY_SPEEDS: [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0]; // playback speed steps
+pub const REPLAY_DEFAULT_SPEED: usize = 2; // index into REPLAY_SPEEDS
+pub const REPLAY_SEEK_SECONDS: f32 = 5.0; // jump for each seek key press
+
+// Rows of the hidden vanish zone drawn above the board
+pub const VISIBLE_BUFFER_ROWS: usize = 1;
+
+// UI panel position (the panel sits right of the board)
+pub const UI_GAP: f32 = 50.0; // space between board and panel
+pub const UI_COLUMN_WIDTH: f32 = 200.0;
+pub const UI_PANEL_WIDTH: f32 = UI_COLUMN_WIDTH * 2.0;
+pub const UI_OFFSET_Y: f32 = 50.0;
+
+// Piece previews (next queue and hold)
+pub const PREVIEW_BLOCK_SIZE: f32 = 20.0;
+pub const PREVIEW_SLOT_HEIGHT: f32 = 50.0; // vertical space per piece in the next queue
+
+// Colors
+pub const COLOR_BACKGROUND: Color = Color::new(0.1, 0.1, 0.12, 1.0);
+pub const COLOR_GRID: Color = Color::new(0.2, 0.2, 0.25, 1.0);
+pub const COLOR_TEXT: Color = Color::new(0.9, 0.9, 0.9, 1.0);
+pub const COLOR_GHOST: f32 = 0.3; // Alpha value for ghost piece
+pub const COLOR_GARBAGE: Color = Color::new(0.5, 0.5, 0.5, 1.0);
+pub const COLOR_BANNER: Color = Color::new(1.0, 0.85, 0.2, 1.0);
+pub const COLOR_AHEAD: Color = Color::new(0.3, 0.9, 0.4, 1.0); // split faster than the personal best
+pub const COLOR_BEHIND: Color = Color::new(0.95, 0.35, 0.3, 1.0); // split slower than the personal best
+
+// Banner timing
+pub const BANNER_DURATION: f32 = 2.0; // seconds a banner stays on screen
+
+// Autoshift defaults (seconds), overridable in the settings file
+pub const DEFAULT_DAS: f32 = 0.167; // delay before a held direction starts repeating
+pub const DEFAULT_ARR: f32 = 0.033; // delay between repeats, 0 shifts straight to the wall
+
+// High score file
+pub const HIGHSCORE_FILE: &str = "highscore.txt";
+// Split times of the fastest Sprint
+pub const SPRINT_BEST_FILE: &str = "sprint_best.txt";
+// Best Ultra scores, one `<score> <player>` line each
+pub const ULTRA_BEST_FILE: &str = "ultra_best.txt";
+pub const ULTRA_TABLE_SIZE: usize = 10;
+
+// Player settings file
+pub const SETTINGS_FILE: &str = "settings.txt";
+pub const REPLAY_DIR: &str = "replays";
+pub const SAVE_FILE: &str = "savegame.txt";
+pub const DEFAULT_PLAYER_NAME: &str = "Player";
@@ -0,0 +1,183 @@
+//! Keyboard focus, so the game can pause when the player switches away.
+//!
+//! Miniquad reports focus changes to its event handler, but macroquad's
+//! handler drops them. On Linux the X server is asked directly which window
+//! is active instead. Where it can't be asked (other platforms, Wayland
+//! without XWayland, a window manager that doesn't publish the active window)
+//! focus is unknown and never counts as lost.
+
+use super::constants::{FOCUS_POLL_TIME, WM_CLASS};
+
+/// Polls whether the game window has keyboard focus.
+pub struct Focus {
+    probe: Option<Probe>,
+    focused: bool,
+    poll_time: f32,
+}
+
+impl Focus {
+    pub fn new() -> Self {
+        Self {
+            probe: Probe::open(),
+            // Focus only counts as lost once it has been seen, so a window
+            // manager slow to focus a new window doesn't pause the first game
+            focused: false,
+            poll_time: 0.0,
+        }
+    }
+
+    /// True on the frame the window is found to have lost focus. Focus is
+    /// checked every `FOCUS_POLL_TIME` seconds.
+    pub fn lost(&mut self, delta_time: f32) -> bool {
+        self.poll_time += delta_time;
+        if self.poll_time < FOCUS_POLL_TIME {
+            return false;
+        }
+        self.poll_time = 0.0;
+
+        let focused = self
+            .probe
+            .as_mut()
+            .and_then(|probe| probe.has_focus())
+            .unwrap_or(true);
+        let lost = self.focused && !focused;
+        self.focused = focused;
+        lost
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+struct Probe;
+
+#[cfg(not(target_os = "linux"))]
+impl Probe {
+    fn open() -> Option<Self> {
+        None
+    }
+
+    fn has_focus(&mut self) -> Option<bool> {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+use x11::Probe;
+
+#[cfg(target_os = "linux")]
+mod x11 {
+    use super::WM_CLASS;
+    use macroquad::miniquad::native::linux_x11::libx11::{
+        _XPrivDisplay, AnyPropertyType, Atom, Display, LibX11, Success, Window, XErrorEvent,
+    };
+    use std::ffi::{c_int, c_long, c_short, c_uchar, c_ulong};
+    use std::{mem, ptr, slice};
+
+    /// A second connection to the X server, used only to read window properties.
+    pub struct Probe {
+        libx11: LibX11,
+        display: *mut Display,
+        root: Window,
+        active_window: Atom,
+        wm_class: Atom,
+    }
+
+    impl Probe {
+        /// Connects to the X server, or `None` when there is no libX11 or no display.
+        pub fn open() -> Option<Self> {
+            let libx11 = LibX11::try_load().ok()?;
+            unsafe {
+                let display = (libx11.XOpenDisplay)(ptr::null());
+                if display.is_null() {
+                    return None;
+                }
+                // Xlib's DefaultRootWindow macro
+                let private = display as _XPrivDisplay;
+                let root = (*(*private)
+                    .screens
+                    .offset((*private).default_screen as isize))
+                .root;
+                let active_window =
+                    (libx11.XInternAtom)(display, c"_NET_ACTIVE_WINDOW".as_ptr(), 0);
+                let wm_class = (libx11.XInternAtom)(display, c"WM_CLASS".as_ptr(), 0);
+                Some(Self {
+                    libx11,
+                    display,
+                    root,
+                    active_window,
+                    wm_class,
+                })
+            }
+        }
+
+        /// Whether the active window is the game's, told apart by its `WM_CLASS`.
+        /// `None` if the window manager doesn't publish the active window.
+        pub fn has_focus(&mut self) -> Option<bool> {
+            let active = self.property(self.root, self.active_window)?;
+            let window =
+                Window::from_ne_bytes(active.get(..mem::size_of::<Window>())?.try_into().ok()?);
+            if window == 0 {
+                // Nothing has focus, e.g. while switching desktops
+                return Some(false);
+            }
+            // WM_CLASS is the instance name and the class name, each ending in a NUL
+            let class = self.property(window, self.wm_class).unwrap_or_default();
+            let instance = class.split(|&byte| byte == 0).next().unwrap_or_default();
+            Some(instance == WM_CLASS.as_bytes())
+        }
+
+        /// The raw bytes of a window property, or `None` if it is unset or
+        /// the window is gone.
+        fn property(&self, window: Window, property: Atom) -> Option<Vec<u8>> {
+            unsafe extern "C" fn ignore_error(_: *mut Display, _: *mut XErrorEvent) -> c_int {
+                0
+            }
+
+            let mut actual_type: Atom = 0;
+            let mut format: c_int = 0;
+            let mut items: c_ulong = 0;
+            let mut bytes_after: c_ulong = 0;
+            let mut data: *mut c_uchar = ptr::null_mut();
+            unsafe {
+                // The active window can close before it is read; Xlib's default
+                // handler would exit the whole game on the resulting BadWindow
+                let previous = (self.libx11.XSetErrorHandler)(Some(ignore_error));
+                let status = (self.libx11.XGetWindowProperty)(
+                    self.display,
+                    window,
+                    property,
+                    0,
+                    64,
+                    0,
+                    AnyPropertyType,
+                    &mut actual_type,
+                    &mut format,
+                    &mut items,
+                    &mut bytes_after,
+                    &mut data,
+                );
+                (self.libx11.XSetErrorHandler)(previous);
+                if status != Success || data.is_null() {
+                    return None;
+                }
+                // Xlib hands back 32-bit items as longs
+                let item_size = match format {
+                    8 => 1,
+                    16 => mem::size_of::<c_short>(),
+                    32 => mem::size_of::<c_long>(),
+                    _ => 0,
+                };
+                let bytes = slice::from_raw_parts(data, items as usize * item_size).to_vec();
+                (self.libx11.XFree)(data.cast());
+                Some(bytes)
+            }
+        }
+    }
+
+    impl Drop for Probe {
+        fn drop(&mut self) {
+            unsafe {
+                (self.libx11.XCloseDisplay)(self.display);
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use macroquad::prelude::*;
+use tetris::constants::MAX_START_LEVEL;
+use tetris::rules::{GameMode, Rules};
+use super::constants::*;
+use super::render::draw_centered_text;
+use super::settings::Settings;
+
+/// The mode and Marathon start level the next game is played with, picked
+/// on the game over screen or in the pause menu and started with R.
+///
+/// M steps through the modes and Up and Down change the start level. Both
+/// start on the settings file's.
+pub struct GameSelect {
+    pub mode: GameMode,
+    pub start_level: u32,
+}
+
+impl GameSelect {
+    pub fn new(settings: &Settings) -> Self {
+        GameSelect {
+            mode: settings.rules.mode,
+            start_level: settings.rules.start_level,
+        }
+    }
+
+    pub fn update(&mut self) {
+        if is_key_pressed(KeyCode::M) {
+            let index = GameMode::ALL.iter().position(|&mode| mode == self.mode).unwrap_or(0);
+            self.mode = GameMode::ALL[(index + 1) % GameMode::ALL.len()];
+        }
+        // Only Marathon has a start level
+        if self.mode == GameMode::Marathon {
+            if is_key_pressed(KeyCode::Up) {
+                self.start_level = (self.start_level + 1).min(MAX_START_LEVEL);
+            }
+            if is_key_pressed(KeyCode::Down) {
+                self.start_level = self.start_level.saturating_sub(1).max(1);
+            }
+        }
+    }
+
+    /// Rules for the next game: the settings file's, in the chosen mode and
+    /// from the chosen start level.
+    pub fn rules(&self, settings: &Settings) -> Rules {
+        Rules {
+            start_level: self.start_level,
+            ..settings.rules_for(self.mode)
+        }
+    }
+
+    /// Draws the choice along the bottom of the screen.
+    pub fn draw(&self) {
+        let mut text = format!("M Mode: {}", self.mode.name().to_uppercase());
+        if self.mode == GameMode::Marathon {
+            text += &format!("   ↑ ↓ Start Level: {}", self.start_level);
+        }
+        draw_centered_text(&text, SCREEN_HEIGHT - 40.0, 24.0, COLOR_TEXT);
+    }
+}
@@ -0,0 +1,126 @@
+use super::constants::{HIGHSCORE_FILE, SPRINT_BEST_FILE, ULTRA_BEST_FILE, ULTRA_TABLE_SIZE};
+use std::cmp::Reverse;
+use std::fs;
+use tetris::game::GameState;
+use tetris::rules::GameMode;
+
+/// A finished Ultra game in the best-score table.
+#[derive(Debug, Clone)]
+pub struct UltraScore {
+    pub score: u64,
+    pub player: String,
+}
+
+/// Personal bests, each kept in its own file.
+pub struct Records {
+    pub high_score: u64,
+    /// Split ticks of the fastest Sprint, empty until one is finished
+    pub sprint_splits: Vec<u64>,
+    /// Best Ultra scores, highest first, at most `ULTRA_TABLE_SIZE`
+    pub ultra_scores: Vec<UltraScore>,
+    /// Place in `ultra_scores` of the last Ultra game recorded, if it made the table
+    pub ultra_rank: Option<usize>,
+}
+
+impl Records {
+    pub fn load() -> Self {
+        Records {
+            high_score: load_high_score(),
+            sprint_splits: load_sprint_splits(),
+            ultra_scores: load_ultra_scores(),
+            ultra_rank: None,
+        }
+    }
+
+    /// Sprint finishing time in ticks, if one was finished.
+    pub fn sprint_best(&self) -> Option<u64> {
+        self.sprint_splits.last().copied()
+    }
+
+    /// Takes in a game that just ended and saves the records it improved.
+    pub fn record(&mut self, game: &GameState, player: &str) {
+        match game.rules.mode {
+            GameMode::Marathon => {
+                self.high_score = self.high_score.max(game.score);
+                save_high_score(self.high_score);
+            }
+            GameMode::Sprint => {
+                if game.finished && self.sprint_best().is_none_or(|best| game.ticks < best) {
+                    self.sprint_splits = game.splits.clone();
+                    save_sprint_splits(&self.sprint_splits);
+                }
+            }
+            GameMode::Ultra => {
+                self.ultra_rank = None;
+                if !game.finished {
+                    return;
+                }
+                // Ties go below the scores already in the table
+                let rank = self.ultra_scores.partition_point(|entry| entry.score >= game.score);
+                if rank < ULTRA_TABLE_SIZE {
+                    let entry = UltraScore {
+                        score: game.score,
+                        player: player.to_string(),
+                    };
+                    self.ultra_scores.insert(rank, entry);
+                    self.ultra_scores.truncate(ULTRA_TABLE_SIZE);
+                    self.ultra_rank = Some(rank);
+                    save_ultra_scores(&self.ultra_scores);
+                }
+            }
+            // Zen keeps no records
+            GameMode::Zen => {}
+        }
+    }
+}
+
+fn load_high_score() -> u64 {
+    fs::read_to_string(HIGHSCORE_FILE)
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn save_high_score(score: u64) {
+    let _ = fs::write(HIGHSCORE_FILE, score.to_string());
+}
+
+fn load_sprint_splits() -> Vec<u64> {
+    fs::read_to_string(SPRINT_BEST_FILE)
+        .ok()
+        .and_then(|s| s.split_whitespace().map(|tick| tick.parse().ok()).collect())
+        .unwrap_or_default()
+}
+
+fn save_sprint_splits(splits: &[u64]) {
+    let ticks: Vec<String> = splits.iter().map(u64::to_string).collect();
+    let _ = fs::write(SPRINT_BEST_FILE, ticks.join(" "));
+}
+
+/// One `<score> <player>` line per entry.
+fn load_ultra_scores() -> Vec<UltraScore> {
+    let Ok(contents) = fs::read_to_string(ULTRA_BEST_FILE) else {
+        return Vec::new();
+    };
+    let mut scores: Vec<UltraScore> = contents
+        .lines()
+        .filter_map(|line| {
+            let (score, player) = line.split_once(' ').unwrap_or((line, ""));
+            Some(UltraScore {
+                score: score.parse().ok()?,
+                player: player.to_string(),
+            })
+        })
+        .collect();
+    scores.sort_by_key(|entry| Reverse(entry.score));
+    scores.truncate(ULTRA_TABLE_SIZE);
+    scores
+}
+
+fn save_ultra_scores(scores: &[UltraScore]) {
+    let lines: Vec<String> = scores
+        .iter()
+        .map(|entry| format!("{} {}", entry.score, entry.player))
+        .collect();
+    let _ = fs::write(ULTRA_BEST_FILE, lines.join("\n"));
+}
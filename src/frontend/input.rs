@@ -0,0 +1,47 @@
+use macroquad::prelude::*;
+
+/// Keyboard state handed to one simulation tick.
+///
+/// Presses are latched until a tick takes them, so a tap is neither lost on
+/// a frame that runs no tick nor repeated on a frame that runs several.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TickInput {
+    pub left_down: bool,
+    pub right_down: bool,
+    pub soft_drop: bool,
+    pub left_pressed: bool,
+    pub right_pressed: bool,
+    pub rotate_cw: bool,
+    pub rotate_ccw: bool,
+    pub rotate_180: bool,
+    pub hard_drop: bool,
+    pub hold: bool,
+}
+
+impl TickInput {
+    /// Reads this frame's keyboard state, keeping presses no tick has taken yet.
+    pub fn poll(&mut self) {
+        self.left_down = is_key_down(KeyCode::Left);
+        self.right_down = is_key_down(KeyCode::Right);
+        self.soft_drop = is_key_down(KeyCode::Down);
+        self.left_pressed |= is_key_pressed(KeyCode::Left);
+        self.right_pressed |= is_key_pressed(KeyCode::Right);
+        self.rotate_cw |= is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::X);
+        self.rotate_ccw |= is_key_pressed(KeyCode::Z);
+        self.rotate_180 |= is_key_pressed(KeyCode::A);
+        self.hard_drop |= is_key_pressed(KeyCode::Space);
+        self.hold |= is_key_pressed(KeyCode::C);
+    }
+
+    /// The input for the next tick. Held keys stay down; presses are used up.
+    pub fn take(&mut self) -> TickInput {
+        let input = *self;
+        *self = TickInput {
+            left_down: self.left_down,
+            right_down: self.right_down,
+            soft_drop: self.soft_drop,
+            ..TickInput::default()
+        };
+        input
+    }
+}
@@ -0,0 +1,39 @@
+use super::constants::*;
+
+/// Screen positions for a board of a given size.
+///
+/// Blocks shrink so that tall or wide boards still fit next to the UI panel.
+pub struct Layout {
+    pub block_size: f32,
+    pub grid_x: f32,
+    pub grid_y: f32,
+    pub grid_width: f32,
+    pub grid_height: f32,
+    pub ui_x: f32,
+    pub ui_column_2_x: f32,
+}
+
+impl Layout {
+    pub fn new(board_width: usize, board_height: usize) -> Self {
+        let available_width = SCREEN_WIDTH - GRID_OFFSET_X - UI_GAP - UI_PANEL_WIDTH;
+        let available_height = SCREEN_HEIGHT - GRID_OFFSET_Y;
+        let block_size = MAX_BLOCK_SIZE
+            .min(available_width / board_width as f32)
+            .min(available_height / board_height as f32)
+            .floor();
+
+        let grid_width = board_width as f32 * block_size;
+        let grid_height = board_height as f32 * block_size;
+        let ui_x = GRID_OFFSET_X + grid_width + UI_GAP;
+
+        Layout {
+            block_size,
+            grid_x: GRID_OFFSET_X,
+            grid_y: GRID_OFFSET_Y,
+            grid_width,
+            grid_height,
+            ui_x,
+            ui_column_2_x: ui_x + UI_COLUMN_WIDTH,
+        }
+    }
+}
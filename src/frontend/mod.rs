@@ -0,0 +1,14 @@
+//! Macroquad frontend: rendering and persistence on top of the headless engine.
+
+pub mod banner;
+pub mod constants;
+pub mod focus;
+pub mod game_select;
+pub mod highscore;
+pub mod input;
+pub mod layout;
+pub mod render;
+pub mod replays;
+pub mod savegame;
+pub mod settings;
+pub mod viewer;
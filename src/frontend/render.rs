@@ -0,0 +1,525 @@
+use macroquad::prelude::*;
+use tetris::constants::*;
+use tetris::game::{Cell, GameState, State, TopOut};
+use tetris::pieces::PieceDef;
+use tetris::rules::GameMode;
+use super::constants::*;
+use super::highscore::Records;
+use super::layout::Layout;
+
+pub fn piece_color(def: &PieceDef) -> Color {
+    let [r, g, b] = def.color;
+    Color::new(r, g, b, 1.0)
+}
+
+pub fn cell_color(game: &GameState, cell: Cell) -> Color {
+    match cell {
+        Cell::Piece(id) => piece_color(game.piece_def(id)),
+        Cell::Garbage => COLOR_GARBAGE,
+    }
+}
+
+/// First grid row that gets drawn, including the peek into the vanish zone.
+const FIRST_DRAWN_ROW: usize = BUFFER_HEIGHT - VISIBLE_BUFFER_ROWS;
+
+/// Screen y of a grid row; the visible field starts at `layout.grid_y`.
+fn row_to_screen_y(layout: &Layout, y: i32) -> f32 {
+    layout.grid_y + (y - BUFFER_HEIGHT as i32) as f32 * layout.block_size
+}
+
+pub fn draw(game: &GameState, records: &Records) {
+    let layout = &Layout::new(game.width(), game.height());
+
+    // Draw background
+    clear_background(COLOR_BACKGROUND);
+
+    // The board stays hidden while paused so the pause can't be used to plan
+    if game.is_paused() {
+        draw_pause_menu(game);
+        return;
+    }
+
+    // Draw grid
+    draw_grid(game, layout);
+
+    // Draw locked pieces
+    draw_locked_pieces(game, layout);
+
+    // Draw ghost and current piece; the next one only spawns once a clear ends
+    if game.state != State::LineClearAnimation {
+        draw_ghost_piece(game, layout);
+        draw_current_piece(game, layout);
+    }
+
+    // Draw UI
+    draw_ui(game, layout, records);
+
+    // Draw game over or results screen
+    if game.game_over {
+        draw_game_over(game.top_out, game.seed);
+    } else if game.finished {
+        draw_finished(game, records);
+    }
+}
+
+fn draw_grid(game: &GameState, layout: &Layout) {
+    for y in 0..game.height() {
+        for x in 0..game.width() {
+            let px = layout.grid_x + x as f32 * layout.block_size;
+            let py = layout.grid_y + y as f32 * layout.block_size;
+            
+            draw_rectangle_lines(px, py, layout.block_size, layout.block_size, 1.0, COLOR_GRID);
+        }
+    }
+
+    // Draw border
+    draw_rectangle_lines(
+        layout.grid_x - 2.0,
+        layout.grid_y - 2.0,
+        layout.grid_width + 4.0,
+        layout.grid_height + 4.0,
+        2.0,
+        COLOR_TEXT,
+    );
+}
+
+fn draw_locked_pieces(game: &GameState, layout: &Layout) {
+    for (y, row) in game.grid.iter().enumerate().skip(FIRST_DRAWN_ROW) {
+        for (x, &cell) in row.iter().enumerate() {
+            if let Some(cell) = cell {
+                let mut color = cell_color(game, cell);
+                let px = layout.grid_x + x as f32 * layout.block_size;
+                let py = row_to_screen_y(layout, y as i32);
+                
+                // Check if this row is being cleared and apply animation
+                if game.state == State::LineClearAnimation && game.lines_being_cleared.contains(&y) {
+                    // Calculate animation progress (0.0 to 1.0)
+                    let progress = game.line_clear_timer / LINE_CLEAR_ANIMATION_DURATION;
+                    
+                    // Fade out: reduce opacity
+                    color.a = 1.0 - progress;
+                    
+                    // Shrink: reduce size toward center
+                    let shrink_amount = progress * (layout.block_size - 2.0) * 0.5;
+                    let block_size = (layout.block_size - 2.0) - (progress * (layout.block_size - 2.0));
+                    
+                    // Center the shrinking block
+                    let offset = shrink_amount;
+                    
+                    draw_rectangle(
+                        px + 1.0 + offset,
+                        py + 1.0 + offset,
+                        block_size,
+                        block_size,
+                        color
+                    );
+                    
+                    // Draw outline with fading
+                    let mut outline_color = WHITE;
+                    outline_color.a = 1.0 - progress;
+                    draw_rectangle_lines(
+                        px + offset,
+                        py + offset,
+                        block_size + 2.0,
+                        block_size + 2.0,
+                        2.0,
+                        outline_color
+                    );
+                } else {
+                    // Normal rendering
+                    draw_rectangle(px + 1.0, py + 1.0, layout.block_size - 2.0, layout.block_size - 2.0, color);
+                    draw_rectangle_lines(px, py, layout.block_size, layout.block_size, 2.0, WHITE);
+                }
+            }
+        }
+    }
+}
+
+fn draw_ghost_piece(game: &GameState, layout: &Layout) {
+    let ghost_y = game.calculate_ghost_y();
+    let blocks = game.current_piece.get_blocks();
+    let mut color = piece_color(game.piece_def(game.current_piece.id));
+    color.a = COLOR_GHOST;
+
+    let y_offset = ghost_y - game.current_piece.y;
+
+    for (x, y) in blocks {
+        if y + y_offset >= FIRST_DRAWN_ROW as i32 {
+            let px = layout.grid_x + x as f32 * layout.block_size;
+            let py = row_to_screen_y(layout, y + y_offset);
+            
+            draw_rectangle(px + 1.0, py + 1.0, layout.block_size - 2.0, layout.block_size - 2.0, color);
+            draw_rectangle_lines(px, py, layout.block_size, layout.block_size, 1.0, color);
+        }
+    }
+}
+
+fn draw_current_piece(game: &GameState, layout: &Layout) {
+    let blocks = game.current_piece.get_blocks();
+    let color = piece_color(game.piece_def(game.current_piece.id));
+
+    for (x, y) in blocks {
+        if y >= FIRST_DRAWN_ROW as i32 {
+            let px = layout.grid_x + x as f32 * layout.block_size;
+            let py = row_to_screen_y(layout, y);
+            
+            draw_rectangle(px + 1.0, py + 1.0, layout.block_size - 2.0, layout.block_size - 2.0, color);
+            draw_rectangle_lines(px, py, layout.block_size, layout.block_size, 2.0, WHITE);
+        }
+    }
+}
+
+/// `m:ss.mmm` for a tick count.
+fn format_time(ticks: u64) -> String {
+    let millis = (ticks as f64 * 1000.0 / FRAMES_PER_SECOND as f64).round() as u64;
+    format!("{}:{:02}.{:03}", millis / 60_000, millis / 1000 % 60, millis % 1000)
+}
+
+fn draw_ui(game: &GameState, layout: &Layout, records: &Records) {
+    let ui_x = layout.ui_x;
+    let mut ui_y = UI_OFFSET_Y;
+    let sprint = game.rules.mode == GameMode::Sprint;
+
+    // Score, which Zen leaves out to keep the pressure off
+    if game.rules.mode == GameMode::Zen {
+        draw_text("MODE", ui_x, ui_y, 24.0, COLOR_TEXT);
+        ui_y += 30.0;
+        draw_text("ZEN", ui_x, ui_y, 32.0, WHITE);
+    } else {
+        draw_text("SCORE", ui_x, ui_y, 24.0, COLOR_TEXT);
+        ui_y += 30.0;
+        draw_text(&format!("{}", game.score), ui_x, ui_y, 32.0, WHITE);
+    }
+    ui_y += 50.0;
+
+    // Timed modes show the mode's best and the clock in place of the high
+    // score and the level: Sprint counts up, Ultra counts down. Zen has no
+    // best, just the time played
+    let (best_label, best, level_label, level) = match game.rules.mode {
+        GameMode::Marathon => (
+            "HIGH SCORE",
+            format!("{}", records.high_score),
+            "LEVEL",
+            format!("{}", game.level),
+        ),
+        GameMode::Sprint => (
+            "BEST",
+            records.sprint_best().map_or("-".to_string(), format_time),
+            "TIME",
+            format_time(game.ticks),
+        ),
+        GameMode::Ultra => (
+            "BEST",
+            format!("{}", records.ultra_scores.first().map_or(0, |entry| entry.score)),
+            "TIME LEFT",
+            format_time(ULTRA_TICKS.saturating_sub(game.ticks)),
+        ),
+        GameMode::Zen => {
+            let seconds = game.ticks / FRAMES_PER_SECOND as u64;
+            (
+                "PLAYED",
+                format!("{}:{:02}", seconds / 60, seconds % 60),
+                "LEVEL",
+                format!("{}", game.level),
+            )
+        }
+    };
+
+    // High score or the mode's best
+    draw_text(best_label, ui_x, ui_y, 24.0, COLOR_TEXT);
+    ui_y += 30.0;
+    draw_text(&best, ui_x, ui_y, 32.0, WHITE);
+    ui_y += 50.0;
+
+    // Level or clock
+    draw_text(level_label, ui_x, ui_y, 24.0, COLOR_TEXT);
+    if let Some(remaining) = game.level_goal_remaining() {
+        // Lines or goal points left before the next level
+        draw_text("GOAL", ui_x + 100.0, ui_y, 24.0, COLOR_TEXT);
+        draw_text(&format!("{}", remaining), ui_x + 100.0, ui_y + 30.0, 32.0, WHITE);
+    }
+    ui_y += 30.0;
+    draw_text(&level, ui_x, ui_y, 32.0, WHITE);
+    ui_y += 50.0;
+
+    // Lines
+    draw_text("LINES", ui_x, ui_y, 24.0, COLOR_TEXT);
+    ui_y += 30.0;
+    let goal = match game.rules.mode {
+        GameMode::Marathon => game.rules.marathon_goal,
+        GameMode::Sprint => Some(SPRINT_LINES),
+        GameMode::Ultra | GameMode::Zen => None,
+    };
+    let lines = match goal {
+        Some(goal) => format!("{}/{}", game.lines_cleared, goal),
+        None => format!("{}", game.lines_cleared),
+    };
+    draw_text(&lines, ui_x, ui_y, 32.0, WHITE);
+    ui_y += 50.0;
+
+    // Hold piece
+    draw_text("HOLD", ui_x, ui_y, 24.0, COLOR_TEXT);
+    ui_y += 30.0;
+    if let Some(held) = game.held_piece {
+        draw_preview_piece(game.piece_def(held), ui_x, ui_y);
+    }
+
+    draw_next_queue(game, layout);
+
+    // Splits replace the controls in Sprint
+    ui_y += 80.0;
+    if sprint {
+        draw_splits(game, records, ui_x, ui_y);
+        return;
+    }
+
+    // Controls
+    draw_text("CONTROLS", ui_x, ui_y, 20.0, COLOR_TEXT);
+    ui_y += 25.0;
+    draw_text("← → Move", ui_x, ui_y, 16.0, COLOR_TEXT);
+    ui_y += 20.0;
+    draw_text("↓ Soft Drop", ui_x, ui_y, 16.0, COLOR_TEXT);
+    ui_y += 20.0;
+    draw_text("Space Hard Drop", ui_x, ui_y, 16.0, COLOR_TEXT);
+    ui_y += 20.0;
+    draw_text("Z/X Rotate", ui_x, ui_y, 16.0, COLOR_TEXT);
+    ui_y += 20.0;
+    draw_text("A Rotate 180", ui_x, ui_y, 16.0, COLOR_TEXT);
+    ui_y += 20.0;
+    draw_text("C Hold", ui_x, ui_y, 16.0, COLOR_TEXT);
+    ui_y += 20.0;
+    draw_text("R Restart  P Pause", ui_x, ui_y, 16.0, COLOR_TEXT);
+}
+
+/// Each split time with how far ahead of (-) or behind (+) the personal best it is.
+fn draw_splits(game: &GameState, records: &Records, ui_x: f32, mut ui_y: f32) {
+    draw_text("SPLITS", ui_x, ui_y, 20.0, COLOR_TEXT);
+    ui_y += 25.0;
+
+    for split in 0..(SPRINT_LINES / SPRINT_SPLIT_LINES) as usize {
+        let lines = (split as u32 + 1) * SPRINT_SPLIT_LINES;
+        draw_text(&format!("{}", lines), ui_x, ui_y, 16.0, COLOR_TEXT);
+        if let Some(&tick) = game.splits.get(split) {
+            draw_text(&format_time(tick), ui_x + 30.0, ui_y, 16.0, WHITE);
+            if let Some(&best) = records.sprint_splits.get(split) {
+                let (sign, difference, color) = if tick <= best {
+                    ("-", best - tick, COLOR_AHEAD)
+                } else {
+                    ("+", tick - best, COLOR_BEHIND)
+                };
+                let seconds = format_time(difference);
+                // Differences are short, so drop the minutes
+                let seconds = seconds.strip_prefix("0:").unwrap_or(&seconds);
+                draw_text(&format!("{}{}", sign, seconds), ui_x + 110.0, ui_y, 16.0, color);
+            }
+        } else {
+            draw_text("-", ui_x + 30.0, ui_y, 16.0, COLOR_TEXT);
+        }
+        ui_y += 20.0;
+    }
+}
+
+fn draw_next_queue(game: &GameState, layout: &Layout) {
+    let ui_x = layout.ui_column_2_x;
+    let mut ui_y = UI_OFFSET_Y;
+
+    draw_text("NEXT", ui_x, ui_y, 24.0, COLOR_TEXT);
+    ui_y += 30.0;
+    for &id in &game.next_pieces {
+        draw_preview_piece(game.piece_def(id), ui_x, ui_y);
+        ui_y += PREVIEW_SLOT_HEIGHT;
+    }
+
+    // Streaks go below the longest possible queue so they never move
+    draw_streaks(game, layout, UI_OFFSET_Y + 50.0 + MAX_PREVIEW_COUNT as f32 * PREVIEW_SLOT_HEIGHT);
+}
+
+fn draw_streaks(game: &GameState, layout: &Layout, top: f32) {
+    let ui_x = layout.ui_column_2_x;
+    let mut ui_y = top;
+
+    // Combo
+    draw_text("COMBO", ui_x, ui_y, 24.0, COLOR_TEXT);
+    ui_y += 30.0;
+    let combo = if game.combo > 0 { format!("x{}", game.combo) } else { "-".to_string() };
+    draw_text(&combo, ui_x, ui_y, 32.0, WHITE);
+    ui_y += 50.0;
+
+    // Back-to-back
+    draw_text("B2B", ui_x, ui_y, 24.0, COLOR_TEXT);
+    ui_y += 30.0;
+    let back_to_back = if game.back_to_back > 1 {
+        format!("x{}", game.back_to_back - 1)
+    } else {
+        "-".to_string()
+    };
+    draw_text(&back_to_back, ui_x, ui_y, 32.0, WHITE);
+}
+
+fn draw_preview_piece(def: &PieceDef, x: f32, y: f32) {
+    let shape = &def.rotations[0];
+    let color = piece_color(def);
+
+    let mut cells = Vec::new();
+    for (i, row) in shape.iter().enumerate() {
+        for (j, &cell) in row.iter().enumerate() {
+            if cell == 1 {
+                cells.push((i, j));
+            }
+        }
+    }
+
+    // Skip the empty rows and columns of the bounding box so every piece sits at (x, y)
+    let top = cells.iter().map(|&(i, _)| i).min().unwrap_or(0);
+    let left = cells.iter().map(|&(_, j)| j).min().unwrap_or(0);
+
+    // Pieces bigger than 4x2 shrink to fit their slot
+    let width = cells.iter().map(|&(_, j)| j - left + 1).max().unwrap_or(1);
+    let height = cells.iter().map(|&(i, _)| i - top + 1).max().unwrap_or(1);
+    let scale = (4.0 / width as f32).min(2.0 / height as f32).min(1.0);
+    let preview_size = PREVIEW_BLOCK_SIZE * scale;
+
+    for (i, j) in cells {
+        let px = x + (j - left) as f32 * preview_size;
+        let py = y + (i - top) as f32 * preview_size;
+
+        draw_rectangle(px + 1.0, py + 1.0, preview_size - 2.0, preview_size - 2.0, color);
+        draw_rectangle_lines(px, py, preview_size, preview_size, 1.0, WHITE);
+    }
+}
+
+/// Draws a line of text centered horizontally on the screen.
+pub fn draw_centered_text(text: &str, y: f32, size: f32, color: Color) {
+    let dims = measure_text(text, None, size as u16, 1.0);
+    draw_text(text, (SCREEN_WIDTH - dims.width) / 2.0, y, size, color);
+}
+
+fn draw_game_over(top_out: Option<TopOut>, seed: u64) {
+    let overlay_color = Color::new(0.0, 0.0, 0.0, 0.7);
+    draw_rectangle(0.0, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT, overlay_color);
+
+    draw_centered_text("GAME OVER", SCREEN_HEIGHT / 2.0 - 50.0, 48.0, WHITE);
+
+    let reason = match top_out {
+        Some(TopOut::BlockOut) => "BLOCK OUT",
+        Some(TopOut::LockOut) => "LOCK OUT",
+        Some(TopOut::GarbageOut) => "TOP OUT",
+        None => "",
+    };
+    draw_centered_text(reason, SCREEN_HEIGHT / 2.0 - 15.0, 24.0, COLOR_TEXT);
+
+    draw_centered_text(
+        "R Restart   V Watch Replay   S Save Replay",
+        SCREEN_HEIGHT / 2.0 + 20.0,
+        24.0,
+        COLOR_TEXT,
+    );
+
+    draw_centered_text(&format!("Seed: {}", seed), SCREEN_HEIGHT / 2.0 + 55.0, 20.0, COLOR_TEXT);
+}
+
+/// Results of a game that reached its mode's goal.
+fn draw_finished(game: &GameState, records: &Records) {
+    let overlay_color = Color::new(0.0, 0.0, 0.0, 0.7);
+    draw_rectangle(0.0, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT, overlay_color);
+
+    match game.rules.mode {
+        GameMode::Sprint => {
+            draw_centered_text("SPRINT COMPLETE", SCREEN_HEIGHT / 2.0 - 90.0, 48.0, WHITE);
+            draw_centered_text(&format_time(game.ticks), SCREEN_HEIGHT / 2.0 - 45.0, 40.0, COLOR_BANNER);
+            // The records already include this game, so a matching best is this run
+            let best = if records.sprint_splits == game.splits {
+                "NEW PERSONAL BEST".to_string()
+            } else {
+                format!("Personal best {}", records.sprint_best().map_or("-".to_string(), format_time))
+            };
+            draw_centered_text(&best, SCREEN_HEIGHT / 2.0 - 10.0, 24.0, COLOR_TEXT);
+        }
+        GameMode::Ultra => {
+            draw_centered_text("TIME UP", SCREEN_HEIGHT / 2.0 - 90.0, 48.0, WHITE);
+            draw_centered_text(&format!("{}", game.score), SCREEN_HEIGHT / 2.0 - 45.0, 40.0, COLOR_BANNER);
+            let rank = match records.ultra_rank {
+                Some(0) => "NEW BEST SCORE".to_string(),
+                Some(rank) => format!("#{} in the best scores", rank + 1),
+                None => format!("Best {}", records.ultra_scores.first().map_or(0, |entry| entry.score)),
+            };
+            draw_centered_text(&rank, SCREEN_HEIGHT / 2.0 - 10.0, 24.0, COLOR_TEXT);
+            draw_ultra_table(records, SCREEN_HEIGHT / 2.0 + 95.0);
+        }
+        GameMode::Marathon => {
+            draw_centered_text("CONGRATULATIONS!", SCREEN_HEIGHT / 2.0 - 90.0, 48.0, COLOR_BANNER);
+            draw_centered_text(
+                &format!("Marathon complete: {} lines", game.lines_cleared),
+                SCREEN_HEIGHT / 2.0 - 50.0,
+                28.0,
+                WHITE,
+            );
+            let high_score = if game.score >= records.high_score { "   NEW HIGH SCORE" } else { "" };
+            draw_centered_text(
+                &format!("Score {}   Level {}{}", game.score, game.level, high_score),
+                SCREEN_HEIGHT / 2.0 - 10.0,
+                24.0,
+                COLOR_TEXT,
+            );
+        }
+        // Zen never ends
+        GameMode::Zen => {}
+    }
+
+    draw_centered_text(
+        "R Restart   V Watch Replay   S Save Replay",
+        SCREEN_HEIGHT / 2.0 + 20.0,
+        24.0,
+        COLOR_TEXT,
+    );
+
+    draw_centered_text(&format!("Seed: {}", game.seed), SCREEN_HEIGHT / 2.0 + 55.0, 20.0, COLOR_TEXT);
+}
+
+/// The top of the Ultra best-score table, with the last game highlighted.
+fn draw_ultra_table(records: &Records, mut y: f32) {
+    for (rank, entry) in records.ultra_scores.iter().take(5).enumerate() {
+        let color = if records.ultra_rank == Some(rank) { COLOR_BANNER } else { COLOR_TEXT };
+        draw_centered_text(&format!("{}. {}  {}", rank + 1, entry.score, entry.player), y, 20.0, color);
+        y += 22.0;
+    }
+}
+
+/// One line on how far a game has got, without the score in Zen.
+fn progress_summary(game: &GameState) -> String {
+    if game.rules.mode == GameMode::Zen {
+        format!("Level {}   Lines {}", game.level, game.lines_cleared)
+    } else {
+        format!("Score {}   Level {}   Lines {}", game.score, game.level, game.lines_cleared)
+    }
+}
+
+fn draw_pause_menu(game: &GameState) {
+    draw_centered_text("PAUSED", SCREEN_HEIGHT / 2.0 - 50.0, 48.0, WHITE);
+    draw_centered_text(
+        &progress_summary(game),
+        SCREEN_HEIGHT / 2.0 - 10.0,
+        24.0,
+        COLOR_TEXT,
+    );
+    draw_centered_text(
+        "P Resume   R Restart   Q Quit",
+        SCREEN_HEIGHT / 2.0 + 30.0,
+        24.0,
+        COLOR_TEXT,
+    );
+}
+
+/// Offers to continue a game saved on the last quit. The board stays hidden.
+pub fn draw_continue_prompt(saved: &GameState) {
+    clear_background(COLOR_BACKGROUND);
+
+    draw_centered_text("SAVED GAME", SCREEN_HEIGHT / 2.0 - 50.0, 48.0, WHITE);
+    draw_centered_text(
+        &progress_summary(saved),
+        SCREEN_HEIGHT / 2.0 - 10.0,
+        24.0,
+        COLOR_TEXT,
+    );
+    draw_centered_text("C Continue   N New Game", SCREEN_HEIGHT / 2.0 + 30.0, 24.0, COLOR_TEXT);
+}
@@ -0,0 +1,16 @@
+use super::constants::REPLAY_DIR;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tetris::game::GameState;
+use tetris::replay_file::ReplayFile;
+
+/// Writes the game's replay to `REPLAY_DIR` and returns the file's path.
+pub fn save_replay(game: &GameState, player: &str) -> Result<String, String> {
+    fs::create_dir_all(REPLAY_DIR).map_err(|err| format!("{}: {}", REPLAY_DIR, err))?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let path = format!("{}/{}-{}.txt", REPLAY_DIR, timestamp, game.score);
+    ReplayFile::from_game(game, player).save(&path)?;
+    Ok(path)
+}
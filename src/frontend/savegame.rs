@@ -0,0 +1,44 @@
+use super::constants::SAVE_FILE;
+use std::fs;
+use tetris::game::GameState;
+use tetris::replay::ReplayPlayer;
+use tetris::replay_file::{ReplayFile, ENGINE_VERSION};
+
+/// Saves an unfinished game to `SAVE_FILE`.
+///
+/// The save is the game's replay: playing it back rebuilds the board, the
+/// pieces, the randomizer and every timer exactly, and the resumed game can
+/// still be replayed from the start.
+pub fn save_game(game: &GameState, player: &str) {
+    let _ = ReplayFile::from_game(game, player).save(SAVE_FILE);
+}
+
+/// The saved game, if there is one that this engine plays back to the saved score.
+/// A save that is corrupt, too long or from another engine is deleted, so
+/// it is treated just like a missing one.
+pub fn load_game() -> Option<GameState> {
+    let game = read_save();
+    if game.is_none() {
+        discard_game();
+    }
+    game
+}
+
+fn read_save() -> Option<GameState> {
+    // Parsing rejects malformed files and replays too long to play back
+    let file = ReplayFile::load(SAVE_FILE).ok()?;
+    if file.engine_version != ENGINE_VERSION {
+        return None;
+    }
+
+    let mut player = ReplayPlayer::new(file.replay);
+    player.run_to_end();
+    let game = player.into_game();
+    let intact = !game.is_over() && game.score == file.score && game.lines_cleared == file.lines;
+    intact.then_some(game)
+}
+
+/// Deletes the save so the game can only be continued once.
+pub fn discard_game() {
+    let _ = fs::remove_file(SAVE_FILE);
+}
@@ -0,0 +1,130 @@
+use super::constants::*;
+use std::fs;
+use std::sync::Arc;
+use tetris::constants::*;
+use tetris::pieces::PieceSet;
+use tetris::randomizer::RandomizerKind;
+use tetris::rules::{parse_marathon_goal, GameMode, Leveling, LockMode, Rules};
+
+/// Player preferences read from `SETTINGS_FILE`.
+///
+/// The file holds one `key = value` pair per line; unknown keys and
+/// malformed lines are ignored and missing keys keep their defaults.
+/// A `piece_set` that fails to load is reported on stderr.
+pub struct Settings {
+    pub das: f32,
+    pub arr: f32,
+    pub rules: Rules,
+    /// Randomizers set with `randomizer.<mode>`, used instead of `randomizer` in that mode
+    pub mode_randomizers: Vec<(GameMode, RandomizerKind)>,
+    /// Name written into saved replays
+    pub player_name: String,
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        let mut settings = Settings::default();
+        let Ok(contents) = fs::read_to_string(SETTINGS_FILE) else {
+            return settings;
+        };
+
+        for line in contents.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "mode" => {
+                    if let Some(mode) = GameMode::from_name(value) {
+                        settings.rules.mode = mode;
+                    }
+                }
+                "start_level" => {
+                    if let Ok(level) = value.parse::<u32>() {
+                        settings.rules.start_level = level.clamp(1, MAX_START_LEVEL);
+                    }
+                }
+                "marathon_goal" => {
+                    if let Some(goal) = parse_marathon_goal(value) {
+                        settings.rules.marathon_goal = goal;
+                    }
+                }
+                "leveling" => {
+                    if let Some(leveling) = Leveling::from_name(value) {
+                        settings.rules.leveling = leveling;
+                    }
+                }
+                "das_ms" => settings.das = parse_millis(value).unwrap_or(settings.das),
+                "arr_ms" => settings.arr = parse_millis(value).unwrap_or(settings.arr),
+                "board_width" => {
+                    if let Ok(width) = value.parse::<usize>() {
+                        settings.rules.width = width.clamp(MIN_GRID_WIDTH, MAX_GRID_WIDTH);
+                    }
+                }
+                "board_height" => {
+                    if let Ok(height) = value.parse::<usize>() {
+                        settings.rules.height = height.clamp(MIN_GRID_HEIGHT, MAX_GRID_HEIGHT);
+                    }
+                }
+                "preview_count" => {
+                    if let Ok(count) = value.parse::<usize>() {
+                        settings.rules.preview_count = count.clamp(1, MAX_PREVIEW_COUNT);
+                    }
+                }
+                "randomizer" => {
+                    if let Some(kind) = RandomizerKind::from_name(value) {
+                        settings.rules.randomizer = kind;
+                    }
+                }
+                "piece_set" => match PieceSet::load(value) {
+                    Ok(piece_set) => settings.rules.piece_set = Arc::new(piece_set),
+                    Err(err) => eprintln!("Ignoring piece set {}", err),
+                },
+                "player_name" if !value.is_empty() => settings.player_name = value.to_string(),
+                "lock_mode" => {
+                    if let Some(mode) = LockMode::from_name(value) {
+                        settings.rules.lock_mode = mode;
+                    }
+                }
+                key => {
+                    let mode = key.strip_prefix("randomizer.").and_then(GameMode::from_name);
+                    if let (Some(mode), Some(kind)) = (mode, RandomizerKind::from_name(value)) {
+                        settings.mode_randomizers.retain(|&(other, _)| other != mode);
+                        settings.mode_randomizers.push((mode, kind));
+                    }
+                }
+            }
+        }
+        settings
+    }
+
+    /// The rules for a game of `mode`, with that mode's randomizer if it has its own.
+    pub fn rules_for(&self, mode: GameMode) -> Rules {
+        let randomizer = self
+            .mode_randomizers
+            .iter()
+            .find(|&&(other, _)| other == mode)
+            .map_or(self.rules.randomizer, |&(_, kind)| kind);
+        Rules {
+            mode,
+            randomizer,
+            ..self.rules.clone()
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            das: DEFAULT_DAS,
+            arr: DEFAULT_ARR,
+            rules: Rules::default(),
+            mode_randomizers: Vec::new(),
+            player_name: DEFAULT_PLAYER_NAME.to_string(),
+        }
+    }
+}
+
+fn parse_millis(value: &str) -> Option<f32> {
+    value.parse::<f32>().ok().filter(|ms| *ms >= 0.0).map(|ms| ms / 1000.0)
+}
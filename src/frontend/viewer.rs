@@ -0,0 +1,119 @@
+use macroquad::prelude::*;
+use tetris::constants::*;
+use tetris::game::GameState;
+use tetris::replay::{Replay, ReplayPlayer};
+use super::constants::*;
+use super::layout::Layout;
+
+/// Plays a recorded game back with pause, speed and seek controls.
+///
+/// Space pauses, Up and Down change the speed, Left and Right jump
+/// `REPLAY_SEEK_SECONDS` and Home goes back to the start.
+pub struct ReplayViewer {
+    player: ReplayPlayer,
+    paused: bool,
+    speed: usize,
+    tick_time: f32,
+}
+
+impl ReplayViewer {
+    pub fn new(replay: Replay) -> Self {
+        ReplayViewer {
+            player: ReplayPlayer::new(replay),
+            paused: false,
+            speed: REPLAY_DEFAULT_SPEED,
+            tick_time: 0.0,
+        }
+    }
+
+    pub fn game(&self) -> &GameState {
+        self.player.game()
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        let seek_ticks = (REPLAY_SEEK_SECONDS * FRAMES_PER_SECOND) as u64;
+
+        if is_key_pressed(KeyCode::Space) {
+            // Playing again from the end starts over
+            if self.paused && self.player.is_finished() {
+                self.player.seek(0);
+            }
+            self.paused = !self.paused;
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.speed = (self.speed + 1).min(REPLAY_SPEEDS.len() - 1);
+        }
+        if is_key_pressed(KeyCode::Down) {
+            self.speed = self.speed.saturating_sub(1);
+        }
+        if is_key_pressed(KeyCode::Left) {
+            self.player.seek(self.player.tick().saturating_sub(seek_ticks));
+        }
+        if is_key_pressed(KeyCode::Right) {
+            self.player.seek(self.player.tick() + seek_ticks);
+        }
+        if is_key_pressed(KeyCode::Home) {
+            self.player.seek(0);
+        }
+
+        if self.paused {
+            return;
+        }
+
+        self.tick_time += delta_time.min(MAX_FRAME_TIME) * REPLAY_SPEEDS[self.speed];
+        while self.tick_time >= TICK_DURATION {
+            self.tick_time -= TICK_DURATION;
+            if self.player.is_finished() {
+                self.paused = true;
+                self.tick_time = 0.0;
+                break;
+            }
+            self.player.step();
+        }
+    }
+
+    /// Draws the playback position and controls under the next queue.
+    pub fn draw(&self) {
+        let game = self.player.game();
+        let layout = Layout::new(game.width(), game.height());
+        let ui_x = layout.ui_column_2_x;
+        let mut ui_y = SCREEN_HEIGHT - 85.0;
+
+        let status = if self.paused { "PAUSED" } else { "REPLAY" };
+        draw_text(status, ui_x, ui_y, 24.0, COLOR_BANNER);
+        draw_text(
+            &format!("{}x", REPLAY_SPEEDS[self.speed]),
+            ui_x + 110.0,
+            ui_y,
+            24.0,
+            COLOR_TEXT,
+        );
+        ui_y += 22.0;
+
+        let length = self.player.replay().ticks;
+        let time = format!("{} / {}", format_ticks(self.player.tick()), format_ticks(length));
+        draw_text(&time, ui_x, ui_y, 20.0, WHITE);
+        ui_y += 8.0;
+
+        // Progress bar
+        let bar_width = UI_COLUMN_WIDTH - 20.0;
+        let progress = if length == 0 {
+            1.0
+        } else {
+            (self.player.tick() as f32 / length as f32).min(1.0)
+        };
+        draw_rectangle(ui_x, ui_y, bar_width, 6.0, COLOR_GRID);
+        draw_rectangle(ui_x, ui_y, bar_width * progress, 6.0, COLOR_BANNER);
+        ui_y += 24.0;
+
+        draw_text("Space Pause  ← → Seek", ui_x, ui_y, 14.0, COLOR_TEXT);
+        ui_y += 16.0;
+        draw_text("↑ ↓ Speed  V Exit", ui_x, ui_y, 14.0, COLOR_TEXT);
+    }
+}
+
+/// `m:ss` for a tick count.
+fn format_ticks(ticks: u64) -> String {
+    let seconds = ticks / FRAMES_PER_SECOND as u64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
@@ -1,174 +1,377 @@
-use macroquad::prelude::*;
 use crate::constants::*;
-use crate::tetromino::{Tetromino, TetrominoType, BagRandomizer};
-use std::fs;
+use crate::gravity;
+use crate::rng;
+use crate::rules::{GameMode, Leveling, LockMode, Rules};
+use crate::scoring::{self, TSpin};
+use crate::srs::Kick;
+use crate::pieces::{PieceDef, PieceId};
+use crate::randomizer::PieceQueue;
+use crate::replay::{Action, Input, Replay};
+use crate::tetromino::Tetromino;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum State {
     Playing,
     LineClearAnimation,
+    /// Nothing moves and no timer runs until `resume`
+    Paused,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Piece(PieceId),
+    Garbage,
+}
+
+/// Why the game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopOut {
+    /// A new piece overlapped the stack where it spawned
+    BlockOut,
+    /// A piece locked entirely inside the hidden vanish zone
+    LockOut,
+    /// Garbage pushed blocks off the top of the matrix
+    GarbageOut,
 }
 
 pub struct GameState {
+    pub rules: Rules,
+    /// Seed of the piece randomizer; the same seed deals the same pieces
+    pub seed: u64,
     pub state: State,
+    /// State to go back to when the game is resumed
+    pub state_before_pause: State,
     pub line_clear_timer: f32,
     pub lines_being_cleared: Vec<usize>,
-    pub grid: Vec<Vec<Option<Color>>>,
+    /// `total_height()` rows; the first `BUFFER_HEIGHT` are the hidden vanish zone
+    pub grid: Vec<Vec<Option<Cell>>>,
+    /// The falling piece; during the line clear animation, the piece that just locked
     pub current_piece: Tetromino,
-    pub next_piece: TetrominoType,
-    pub held_piece: Option<TetrominoType>,
+    /// Upcoming pieces, soonest first, `rules.preview_count` long
+    pub next_pieces: Vec<PieceId>,
+    pub held_piece: Option<PieceId>,
     pub can_hold: bool,
-    pub score: u32,
-    pub high_score: u32,
+    /// Saturates at `u64::MAX` rather than overflowing
+    pub score: u64,
     pub level: u32,
     pub lines_cleared: u32,
+    /// Goal points earned towards the next level under the variable goal
+    pub goal_points: u32,
+    /// Whether the game was lost; `top_out` says how
     pub game_over: bool,
-    pub fall_timer: f32,
+    pub top_out: Option<TopOut>,
+    /// Whether the mode's goal was reached, ending the game without a top-out
+    pub finished: bool,
+    /// Tick of the lock that reached each multiple of `SPRINT_SPLIT_LINES` lines in Sprint
+    pub splits: Vec<u64>,
+    /// Fraction of a row gravity has built up towards the next fall
+    pub fall_progress: f32,
     pub lock_timer: f32,
     pub is_on_ground: bool,
-    pub bag_randomizer: BagRandomizer,
+    /// Grounded moves and rotations made by the current piece since it last reached a new row
+    pub lock_resets: u32,
+    /// Lowest row the current piece has reached
+    pub lowest_row: i32,
+    /// Kick used by the last successful action, if that action was a rotation
+    pub last_rotation_kick: Option<Kick>,
+    /// T-spin classification of the most recently locked piece
+    pub t_spin: TSpin,
+    /// Consecutive line-clearing locks minus one, -1 when no combo is running
+    pub combo: i32,
+    /// Consecutive difficult clears (four or more lines, or T-spins)
+    pub back_to_back: u32,
+    pub perfect_clears: u32,
+    pub piece_queue: PieceQueue,
+    /// Simulation ticks run so far
+    pub ticks: u64,
+    /// Whether soft drop is held
+    pub soft_drop: bool,
+    /// Every action made through `apply`, for replays
+    pub inputs: Vec<Input>,
 }
 
 impl GameState {
     pub fn new() -> Self {
-        let mut bag_randomizer = BagRandomizer::new();
-        let current_type = bag_randomizer.next();
-        let next_type = bag_randomizer.peek();
-        let high_score = Self::load_high_score();
+        Self::with_rules(Rules::default())
+    }
+
+    pub fn with_rules(rules: Rules) -> Self {
+        Self::with_seed(rules, rng::random_seed())
+    }
 
-        GameState {
+    pub fn with_seed(mut rules: Rules, seed: u64) -> Self {
+        rules.preview_count = rules.preview_count.clamp(1, MAX_PREVIEW_COUNT);
+        rules.width = rules.width.clamp(MIN_GRID_WIDTH, MAX_GRID_WIDTH);
+        rules.height = rules.height.clamp(MIN_GRID_HEIGHT, MAX_GRID_HEIGHT);
+        rules.start_level = rules.start_level.clamp(1, MAX_START_LEVEL);
+        // Sprint and Ultra stay at level 1 so results compare, Zen for constant gravity
+        let level = if rules.mode == GameMode::Marathon { rules.start_level } else { 1 };
+        let width = rules.width;
+        let total_height = rules.height + BUFFER_HEIGHT;
+
+        let mut piece_queue = PieceQueue::new(rules.randomizer, &rules.piece_set, seed);
+        let current_type = piece_queue.next();
+        let next_pieces = piece_queue.preview(rules.preview_count);
+
+        let current_piece = Tetromino::new(current_type, rules.piece_set.get(current_type), rules.width);
+        let lowest_row = current_piece.y;
+
+        let mut game = GameState {
+            rules,
+            seed,
             state: State::Playing,
+            state_before_pause: State::Playing,
             line_clear_timer: 0.0,
             lines_being_cleared: Vec::new(),
-            grid: vec![vec![None; GRID_WIDTH]; GRID_HEIGHT],
-            current_piece: Tetromino::new(current_type),
-            next_piece: next_type,
+            grid: vec![vec![None; width]; total_height],
+            current_piece,
+            next_pieces,
             held_piece: None,
             can_hold: true,
             score: 0,
-            high_score,
-            level: 1,
+            level,
             lines_cleared: 0,
+            goal_points: 0,
             game_over: false,
-            fall_timer: 0.0,
+            top_out: None,
+            finished: false,
+            splits: Vec::new(),
+            fall_progress: 0.0,
             lock_timer: 0.0,
             is_on_ground: false,
-            bag_randomizer,
-        }
+            lock_resets: 0,
+            lowest_row,
+            last_rotation_kick: None,
+            t_spin: TSpin::None,
+            combo: -1,
+            back_to_back: 0,
+            perfect_clears: 0,
+            piece_queue,
+            ticks: 0,
+            soft_drop: false,
+            inputs: Vec::new(),
+        };
+        game.start_piece(current_type);
+        game
     }
 
-    pub fn update(&mut self, delta_time: f32, soft_drop: bool) {
-        if self.game_over {
+    /// Advances the game by one `TICK_DURATION` step. The engine only moves
+    /// in whole ticks, so a game plays the same at any frame rate.
+    pub fn tick(&mut self) {
+        if self.is_over() || self.state == State::Paused {
             return;
         }
+        self.ticks += 1;
+        self.run_tick();
 
+        // Ultra's time runs out at the end of a tick
+        if !self.is_over() && self.goal_reached() {
+            self.finish();
+        }
+    }
+
+    fn run_tick(&mut self) {
         // Handle line clear animation
         if self.state == State::LineClearAnimation {
-            self.line_clear_timer += delta_time;
+            self.line_clear_timer += TICK_DURATION;
             
             if self.line_clear_timer >= LINE_CLEAR_ANIMATION_DURATION {
-                // Animation finished, actually clear the lines
-                self.complete_line_clear();
-                self.state = State::Playing;
-                self.line_clear_timer = 0.0;
-                self.lines_being_cleared.clear();
+                // Animation finished, actually clear the lines and deal the
+                // next piece into the space they left
+                self.end_line_clear_animation();
+                self.spawn_next_piece();
+                self.can_hold = true;
             }
             return;
         }
 
-        let fall_speed = if soft_drop {
-            FAST_DROP_SPEED
+        // Gravity, possibly several rows in one tick at high levels
+        self.fall_progress += if self.soft_drop {
+            gravity::soft_drop_rows_per_frame(self.level)
         } else {
-            INITIAL_FALL_SPEED / (self.level as f32)
+            gravity::rows_per_frame(self.level)
         };
 
-        self.fall_timer += delta_time;
+        while self.fall_progress >= 1.0 {
+            if self.check_collision(0, 1) {
+                self.fall_progress = 0.0;
+                break;
+            }
+            self.fall_progress -= 1.0;
+            self.current_piece.y += 1;
+            self.last_rotation_kick = None;
+            // Award points for soft drop
+            if self.soft_drop {
+                self.add_score(u64::from(SCORE_SOFT_DROP));
+            }
+        }
+
+        // Reaching a new lowest row starts the lock delay over
+        if self.current_piece.y > self.lowest_row {
+            self.lowest_row = self.current_piece.y;
+            self.lock_timer = 0.0;
+            self.lock_resets = 0;
+        }
 
         // Check if piece is on ground
-        let was_on_ground = self.is_on_ground;
         self.is_on_ground = self.check_collision(0, 1);
 
         if self.is_on_ground {
-            self.lock_timer += delta_time;
-            if self.lock_timer >= LOCK_DELAY {
+            self.lock_timer += TICK_DURATION;
+
+            // Once the move resets are used up the piece locks as soon as it lands
+            let out_of_resets = self.rules.lock_mode == LockMode::MoveReset
+                && self.lock_resets > MAX_LOCK_RESETS;
+            if self.lock_timer >= LOCK_DELAY || out_of_resets {
                 self.lock_piece();
             }
-        } else {
-            self.lock_timer = 0.0;
         }
+    }
 
-        // Auto-fall
-        if self.fall_timer >= fall_speed {
-            self.fall_timer = 0.0;
-            if !self.is_on_ground {
-                self.current_piece.y += 1;
-                // Award points for soft drop
-                if soft_drop {
-                    self.score += SCORE_SOFT_DROP;
-                }
+    /// Carries out a player action and records it with the current tick.
+    /// Actions are ignored once the game is over or while it is paused, and
+    /// all but soft drop while the line clear animation plays. Returns whether
+    /// it took effect; only those that did are recorded.
+    pub fn apply(&mut self, action: Action) -> bool {
+        if self.is_over() || self.state == State::Paused {
+            return false;
+        }
+        let applied = match action {
+            Action::SoftDrop(held) => {
+                let changed = held != self.soft_drop;
+                self.soft_drop = held;
+                changed
+            }
+            _ if self.state != State::Playing => return false,
+            Action::MoveLeft => self.move_left(),
+            Action::MoveRight => self.move_right(),
+            Action::RotateCw => self.rotate_cw(),
+            Action::RotateCcw => self.rotate_ccw(),
+            Action::Rotate180 => self.rotate_180(),
+            Action::HardDrop => {
+                self.hard_drop();
+                true
             }
+            Action::Hold => self.hold_piece(),
+        };
+        // Actions that changed nothing are left out of the replay
+        if applied {
+            self.inputs.push(Input { tick: self.ticks, action });
         }
+        applied
+    }
 
-        // Reset lock timer if piece was moved off ground
-        if was_on_ground && !self.is_on_ground {
-            self.lock_timer = 0.0;
+    /// Freezes the game. Pausing takes no ticks, so replays are unaffected.
+    pub fn pause(&mut self) {
+        if self.is_over() || self.state == State::Paused {
+            return;
         }
+        self.state_before_pause = self.state;
+        self.state = State::Paused;
     }
 
-    pub fn move_left(&mut self) {
-        if !self.check_collision(-1, 0) {
-            self.current_piece.x -= 1;
+    pub fn resume(&mut self) {
+        if self.state == State::Paused {
+            self.state = self.state_before_pause;
         }
     }
 
-    pub fn move_right(&mut self) {
-        if !self.check_collision(1, 0) {
-            self.current_piece.x += 1;
+    pub fn is_paused(&self) -> bool {
+        self.state == State::Paused
+    }
+
+    /// Whether the game has ended, by topping out or by finishing.
+    pub fn is_over(&self) -> bool {
+        self.game_over || self.finished
+    }
+
+    /// Everything needed to play this game back so far.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            rules: self.rules.clone(),
+            seed: self.seed,
+            inputs: self.inputs.clone(),
+            ticks: self.ticks,
         }
     }
 
-    pub fn rotate_cw(&mut self) {
-        let mut test_piece = self.current_piece.clone();
-        test_piece.rotate_cw();
+    pub fn move_left(&mut self) -> bool {
+        if self.check_collision(-1, 0) {
+            return false;
+        }
+        self.current_piece.x -= 1;
+        self.last_rotation_kick = None;
+        self.on_grounded_move();
+        true
+    }
 
-        // Try direct rotation
-        if !self.check_collision_piece(&test_piece) {
-            self.current_piece = test_piece;
+    pub fn move_right(&mut self) -> bool {
+        if self.check_collision(1, 0) {
+            return false;
+        }
+        self.current_piece.x += 1;
+        self.last_rotation_kick = None;
+        self.on_grounded_move();
+        true
+    }
+
+    /// Applies the lock delay reset rule after a successful move or rotation.
+    fn on_grounded_move(&mut self) {
+        if !self.is_on_ground {
             return;
         }
 
-        // Wall kick attempts (basic SRS-inspired)
-        let kicks = vec![(1, 0), (-1, 0), (0, -1), (1, -1), (-1, -1)];
-        for (dx, dy) in kicks {
-            test_piece.x = self.current_piece.x + dx;
-            test_piece.y = self.current_piece.y + dy;
-            if !self.check_collision_piece(&test_piece) {
-                self.current_piece = test_piece;
-                return;
+        match self.rules.lock_mode {
+            LockMode::MoveReset => {
+                self.lock_resets += 1;
+                if self.lock_resets <= MAX_LOCK_RESETS {
+                    self.lock_timer = 0.0;
+                }
             }
+            LockMode::StepReset => {}
+            LockMode::Infinite => self.lock_timer = 0.0,
         }
     }
 
-    pub fn rotate_ccw(&mut self) {
-        let mut test_piece = self.current_piece.clone();
-        test_piece.rotate_ccw();
+    pub fn rotate_cw(&mut self) -> bool {
+        let mut rotated = self.current_piece.clone();
+        rotated.rotate_cw(self.piece_def(rotated.id));
+        self.try_rotate(rotated)
+    }
 
-        // Try direct rotation
-        if !self.check_collision_piece(&test_piece) {
-            self.current_piece = test_piece;
-            return;
-        }
+    pub fn rotate_ccw(&mut self) -> bool {
+        let mut rotated = self.current_piece.clone();
+        rotated.rotate_ccw(self.piece_def(rotated.id));
+        self.try_rotate(rotated)
+    }
 
-        // Wall kick attempts
-        let kicks = vec![(1, 0), (-1, 0), (0, -1), (1, -1), (-1, -1)];
-        for (dx, dy) in kicks {
-            test_piece.x = self.current_piece.x + dx;
-            test_piece.y = self.current_piece.y + dy;
-            if !self.check_collision_piece(&test_piece) {
-                self.current_piece = test_piece;
-                return;
+    pub fn rotate_180(&mut self) -> bool {
+        let mut rotated = self.current_piece.clone();
+        rotated.rotate_180(self.piece_def(rotated.id));
+        self.try_rotate(rotated)
+    }
+
+    /// Tries the piece's kick offsets for the transition into `rotated`'s state
+    /// and keeps the first position that fits. Returns whether it succeeded.
+    fn try_rotate(&mut self, mut rotated: Tetromino) -> bool {
+        let piece_set = Arc::clone(&self.rules.piece_set);
+        let kicks = piece_set
+            .get(rotated.id)
+            .kicks
+            .offsets(self.current_piece.rotation, rotated.rotation);
+
+        for &(dx, dy) in kicks {
+            rotated.x = self.current_piece.x + dx;
+            rotated.y = self.current_piece.y - dy;
+            if !self.check_collision_piece(&rotated) {
+                self.current_piece = rotated;
+                self.last_rotation_kick = Some((dx, dy));
+                self.on_grounded_move();
+                return true;
             }
         }
+        false
     }
 
     pub fn hard_drop(&mut self) {
@@ -176,22 +379,25 @@ impl GameState {
         let drop_distance = ghost_y - self.current_piece.y;
         
         // Award points for hard drop (2 points per cell)
-        self.score += (drop_distance as u32) * SCORE_HARD_DROP;
+        self.add_score(drop_distance as u64 * u64::from(SCORE_HARD_DROP));
         
+        if drop_distance > 0 {
+            self.last_rotation_kick = None;
+        }
         self.current_piece.y = ghost_y;
         self.lock_piece();
     }
 
-    pub fn hold_piece(&mut self) {
+    pub fn hold_piece(&mut self) -> bool {
         if !self.can_hold {
-            return;
+            return false;
         }
 
-        let current_type = self.current_piece.tetromino_type;
+        let current_type = self.current_piece.id;
         
         if let Some(held_type) = self.held_piece {
             // Swap with held piece
-            self.current_piece = Tetromino::new(held_type);
+            self.start_piece(held_type);
             self.held_piece = Some(current_type);
         } else {
             // Hold current and spawn next
@@ -200,6 +406,7 @@ impl GameState {
         }
 
         self.can_hold = false;
+        true
     }
 
     fn check_collision(&self, dx: i32, dy: i32) -> bool {
@@ -208,7 +415,7 @@ impl GameState {
             let new_x = x + dx;
             let new_y = y + dy;
 
-            if new_x < 0 || new_x >= GRID_WIDTH as i32 || new_y >= GRID_HEIGHT as i32 {
+            if new_x < 0 || new_x >= self.width() as i32 || new_y >= self.total_height() as i32 {
                 return true;
             }
 
@@ -222,7 +429,7 @@ impl GameState {
     fn check_collision_piece(&self, piece: &Tetromino) -> bool {
         let blocks = piece.get_blocks();
         for (x, y) in blocks {
-            if x < 0 || x >= GRID_WIDTH as i32 || y >= GRID_HEIGHT as i32 {
+            if x < 0 || x >= self.width() as i32 || y >= self.total_height() as i32 {
                 return true;
             }
 
@@ -245,58 +452,249 @@ impl GameState {
         ghost_y
     }
 
+    /// Classifies the current piece with the 3-corner rule. Only a T that
+    /// got into place by rotating can be a T-spin.
+    fn detect_t_spin(&self) -> TSpin {
+        let piece = &self.current_piece;
+        let Some((kick_x, kick_y)) = self.last_rotation_kick else {
+            return TSpin::None;
+        };
+        if self.piece_def(piece.id).name != "T" || piece.shape.len() != 3 {
+            return TSpin::None;
+        }
+
+        // Corners of the 3x3 box around the T's center, out of bounds counts as filled
+        let occupied = |dx: i32, dy: i32| {
+            let x = piece.x + dx;
+            let y = piece.y + dy;
+            if x < 0 || x >= self.width() as i32 || y >= self.total_height() as i32 {
+                return true;
+            }
+            y >= 0 && self.grid[y as usize][x as usize].is_some()
+        };
+        let top_left = occupied(0, 0);
+        let top_right = occupied(2, 0);
+        let bottom_left = occupied(0, 2);
+        let bottom_right = occupied(2, 2);
+
+        let corners = [top_left, top_right, bottom_left, bottom_right]
+            .iter()
+            .filter(|&&c| c)
+            .count();
+        if corners < 3 {
+            return TSpin::None;
+        }
+
+        // The two corners on the side the T points towards
+        let (front_a, front_b) = match piece.rotation {
+            0 => (top_left, top_right),
+            1 => (top_right, bottom_right),
+            2 => (bottom_left, bottom_right),
+            _ => (top_left, bottom_left),
+        };
+
+        // The long (1, 2) kick always counts as a full T-spin
+        if (front_a && front_b) || (kick_x.abs() == 1 && kick_y.abs() == 2) {
+            TSpin::Full
+        } else {
+            TSpin::Mini
+        }
+    }
+
     fn lock_piece(&mut self) {
+        self.t_spin = self.detect_t_spin();
+
         let blocks = self.current_piece.get_blocks();
-        let color = self.current_piece.color();
+        let piece_type = self.current_piece.id;
 
-        for (x, y) in blocks {
-            if y >= 0 && y < GRID_HEIGHT as i32 && x >= 0 && x < GRID_WIDTH as i32 {
-                self.grid[y as usize][x as usize] = Some(color);
+        for &(x, y) in &blocks {
+            if y >= 0 && y < self.total_height() as i32 && x >= 0 && x < self.width() as i32 {
+                self.grid[y as usize][x as usize] = Some(Cell::Piece(piece_type));
             }
         }
 
+        // Locking with no block in the visible field ends the game; Zen
+        // carries on and the next spawn makes room
+        let above_field = blocks.iter().all(|&(_, y)| y < BUFFER_HEIGHT as i32);
+        if above_field && self.rules.mode != GameMode::Zen {
+            self.top_out(TopOut::LockOut);
+            return;
+        }
+
         self.clear_lines();
+        if self.goal_reached() {
+            self.finish();
+            return;
+        }
+        // A clear deals the next piece once its rows are gone, so a full row
+        // in the spawn area doesn't block it out
+        if self.state == State::LineClearAnimation {
+            return;
+        }
         self.spawn_next_piece();
         self.can_hold = true;
-        self.lock_timer = 0.0;
-        self.is_on_ground = false;
     }
 
     fn spawn_next_piece(&mut self) {
-        let next_type = self.bag_randomizer.next();
-        self.current_piece = Tetromino::new(next_type);
-        self.next_piece = self.bag_randomizer.peek();
-        self.fall_timer = 0.0;
+        let next_type = self.piece_queue.next();
+        self.start_piece(next_type);
+        self.next_pieces = self.piece_queue.preview(self.rules.preview_count);
+    }
+
+    fn top_out(&mut self, reason: TopOut) {
+        self.game_over = true;
+        self.top_out = Some(reason);
+    }
+
+    /// Whether the mode's end is reached: Marathon's goal or Sprint's lines,
+    /// counting those still being cleared, or Ultra's time.
+    fn goal_reached(&self) -> bool {
+        let lines = self.lines_cleared + self.lines_being_cleared.len() as u32;
+        match self.rules.mode {
+            GameMode::Marathon => self.rules.marathon_goal.is_some_and(|goal| lines >= goal),
+            GameMode::Sprint => lines >= SPRINT_LINES,
+            GameMode::Ultra => self.ticks >= ULTRA_TICKS,
+            GameMode::Zen => false,
+        }
+    }
+
+    /// Ends the game cleanly. A clear still animating is scored at once so
+    /// the result is final.
+    fn finish(&mut self) {
+        if self.state == State::LineClearAnimation {
+            self.end_line_clear_animation();
+        }
+        self.finished = true;
+    }
+
+    /// Pushes the stack up by `count` garbage rows, each with a hole at `hole_column`.
+    /// Blocks pushed off the top of the matrix end the game.
+    pub fn add_garbage(&mut self, count: usize, hole_column: usize) {
+        if self.is_over() || count == 0 {
+            return;
+        }
+
+        let count = count.min(self.total_height());
+        let overflow = self.grid[..count]
+            .iter()
+            .any(|row| row.iter().any(|cell| cell.is_some()));
+
+        self.grid.drain(..count);
+        for _ in 0..count {
+            let mut row = vec![Some(Cell::Garbage); self.width()];
+            if hole_column < self.width() {
+                row[hole_column] = None;
+            }
+            self.grid.push(row);
+        }
+
+        // Rows waiting on the clear animation moved up with the stack
+        self.lines_being_cleared.retain(|&y| y >= count);
+        for y in self.lines_being_cleared.iter_mut() {
+            *y -= count;
+        }
+
+        if overflow && self.rules.mode != GameMode::Zen {
+            self.top_out(TopOut::GarbageOut);
+            return;
+        }
+        // No piece is falling until the clear animation ends
+        if self.state == State::LineClearAnimation {
+            return;
+        }
+
+        // The falling piece rides up on top of the garbage if it now overlaps
+        while self.check_collision_piece(&self.current_piece) && self.current_piece.y > 0 {
+            self.current_piece.y -= 1;
+        }
+        if self.check_collision_piece(&self.current_piece) {
+            if self.rules.mode == GameMode::Zen {
+                self.make_room();
+            } else {
+                self.top_out(TopOut::GarbageOut);
+            }
+        }
+        self.lowest_row = self.lowest_row.min(self.current_piece.y);
+    }
+
+    /// Zen's answer to a top-out: shifts the stack down, dropping its bottom
+    /// rows, until the current piece fits.
+    fn make_room(&mut self) {
+        while self.check_collision_piece(&self.current_piece) && !self.is_board_empty() {
+            self.grid.pop();
+            self.grid.insert(0, vec![None; self.width()]);
+
+            // Rows waiting on the clear animation moved down with the stack
+            let bottom = self.total_height() - 1;
+            self.lines_being_cleared.retain(|&y| y != bottom);
+            for y in self.lines_being_cleared.iter_mut() {
+                *y += 1;
+            }
+        }
+    }
+
+    /// Puts a fresh piece at the spawn position with its timers cleared.
+    fn start_piece(&mut self, piece_type: PieceId) {
+        self.current_piece = Tetromino::new(piece_type, self.piece_def(piece_type), self.width());
+        self.fall_progress = 0.0;
+        self.lock_timer = 0.0;
+        self.lock_resets = 0;
+        self.is_on_ground = false;
+        self.last_rotation_kick = None;
 
         // Check if game over (piece can't spawn)
         if self.check_collision_piece(&self.current_piece) {
-            self.game_over = true;
-            if self.score > self.high_score {
-                self.high_score = self.score;
-                Self::save_high_score(self.high_score);
+            if self.rules.mode != GameMode::Zen {
+                self.top_out(TopOut::BlockOut);
+                return;
             }
+            self.make_room();
         }
+
+        // The piece drops one row straight away if nothing is in the way
+        if !self.check_collision(0, 1) {
+            self.current_piece.y += 1;
+        }
+        self.lowest_row = self.current_piece.y;
     }
 
     fn clear_lines(&mut self) {
         let mut lines_to_clear = Vec::new();
 
-        for y in 0..GRID_HEIGHT {
-            if self.grid[y].iter().all(|cell| cell.is_some()) {
+        for (y, row) in self.grid.iter().enumerate() {
+            if row.iter().all(|cell| cell.is_some()) {
                 lines_to_clear.push(y);
             }
         }
 
         if lines_to_clear.is_empty() {
+            // T-spins without a line clear still score
+            self.award_clear_score(0);
+            self.update_level(0);
             return;
         }
 
+        // Sprint splits are timed at the lock, not after the animation
+        if self.rules.mode == GameMode::Sprint {
+            let lines = (self.lines_cleared + lines_to_clear.len() as u32).min(SPRINT_LINES);
+            while (self.splits.len() as u32) < lines / SPRINT_SPLIT_LINES {
+                self.splits.push(self.ticks);
+            }
+        }
+
         // Start the line clear animation
         self.lines_being_cleared = lines_to_clear;
         self.state = State::LineClearAnimation;
         self.line_clear_timer = 0.0;
     }
 
+    fn end_line_clear_animation(&mut self) {
+        self.complete_line_clear();
+        self.state = State::Playing;
+        self.line_clear_timer = 0.0;
+        self.lines_being_cleared.clear();
+    }
+
     fn complete_line_clear(&mut self) {
         if self.lines_being_cleared.is_empty() {
             return;
@@ -314,278 +712,117 @@ impl GameState {
 
         // Add empty lines at the top
         for _ in 0..num_lines {
-            self.grid.insert(0, vec![None; GRID_WIDTH]);
+            self.grid.insert(0, vec![None; self.width()]);
         }
 
         // Update score
         let lines_count = self.lines_being_cleared.len() as u32;
         self.lines_cleared += lines_count;
-        
-        let base_score = match lines_count {
-            1 => SCORE_SINGLE,
-            2 => SCORE_DOUBLE,
-            3 => SCORE_TRIPLE,
-            4 => SCORE_TETRIS,
-            _ => 0,
-        };
-
-        self.score += base_score * self.level;
+        self.award_clear_score(lines_count);
 
-        // Update level (every 10 lines)
-        self.level = (self.lines_cleared / 10) + 1;
-
-        // Update high score
-        if self.score > self.high_score {
-            self.high_score = self.score;
-        }
+        self.update_level(lines_count);
     }
 
-    pub fn draw(&self) {
-        // Draw background
-        clear_background(COLOR_BACKGROUND);
-
-        // Draw grid
-        self.draw_grid();
-
-        // Draw locked pieces
-        self.draw_locked_pieces();
-
-        // Draw ghost piece
-        self.draw_ghost_piece();
-
-        // Draw current piece
-        self.draw_current_piece();
-
-        // Draw UI
-        self.draw_ui();
-
-        // Draw game over screen
-        if self.game_over {
-            self.draw_game_over();
+    /// Counts a lock clearing `lines` towards Marathon's next level.
+    fn update_level(&mut self, lines: u32) {
+        if self.rules.mode != GameMode::Marathon {
+            return;
         }
-    }
-
-    fn draw_grid(&self) {
-        for y in 0..GRID_HEIGHT {
-            for x in 0..GRID_WIDTH {
-                let px = GRID_OFFSET_X + x as f32 * BLOCK_SIZE;
-                let py = GRID_OFFSET_Y + y as f32 * BLOCK_SIZE;
-                
-                draw_rectangle_lines(px, py, BLOCK_SIZE, BLOCK_SIZE, 1.0, COLOR_GRID);
+        match self.rules.leveling {
+            Leveling::Fixed => {
+                self.level = self.rules.start_level + self.lines_cleared / LINES_PER_LEVEL;
             }
-        }
-
-        // Draw border
-        draw_rectangle_lines(
-            GRID_OFFSET_X - 2.0,
-            GRID_OFFSET_Y - 2.0,
-            GRID_WIDTH as f32 * BLOCK_SIZE + 4.0,
-            GRID_HEIGHT as f32 * BLOCK_SIZE + 4.0,
-            2.0,
-            COLOR_TEXT,
-        );
-    }
-
-    fn draw_locked_pieces(&self) {
-        for y in 0..GRID_HEIGHT {
-            for x in 0..GRID_WIDTH {
-                if let Some(mut color) = self.grid[y][x] {
-                    let px = GRID_OFFSET_X + x as f32 * BLOCK_SIZE;
-                    let py = GRID_OFFSET_Y + y as f32 * BLOCK_SIZE;
-                    
-                    // Check if this row is being cleared and apply animation
-                    if self.state == State::LineClearAnimation && self.lines_being_cleared.contains(&y) {
-                        // Calculate animation progress (0.0 to 1.0)
-                        let progress = self.line_clear_timer / LINE_CLEAR_ANIMATION_DURATION;
-                        
-                        // Fade out: reduce opacity
-                        color.a = 1.0 - progress;
-                        
-                        // Shrink: reduce size toward center
-                        let shrink_amount = progress * (BLOCK_SIZE - 2.0) * 0.5;
-                        let block_size = (BLOCK_SIZE - 2.0) - (progress * (BLOCK_SIZE - 2.0));
-                        
-                        // Center the shrinking block
-                        let offset = shrink_amount;
-                        
-                        draw_rectangle(
-                            px + 1.0 + offset,
-                            py + 1.0 + offset,
-                            block_size,
-                            block_size,
-                            color
-                        );
-                        
-                        // Draw outline with fading
-                        let mut outline_color = WHITE;
-                        outline_color.a = 1.0 - progress;
-                        draw_rectangle_lines(
-                            px + offset,
-                            py + offset,
-                            block_size + 2.0,
-                            block_size + 2.0,
-                            2.0,
-                            outline_color
-                        );
-                    } else {
-                        // Normal rendering
-                        draw_rectangle(px + 1.0, py + 1.0, BLOCK_SIZE - 2.0, BLOCK_SIZE - 2.0, color);
-                        draw_rectangle_lines(px, py, BLOCK_SIZE, BLOCK_SIZE, 2.0, WHITE);
-                    }
+            Leveling::Variable => {
+                // Points past the goal carry over to the next level
+                self.goal_points += scoring::goal_points(lines, self.t_spin);
+                while self.goal_points >= self.level * GOAL_POINTS_PER_LEVEL {
+                    self.goal_points -= self.level * GOAL_POINTS_PER_LEVEL;
+                    self.level += 1;
                 }
             }
         }
     }
 
-    fn draw_ghost_piece(&self) {
-        let ghost_y = self.calculate_ghost_y();
-        let blocks = self.current_piece.get_blocks();
-        let mut color = self.current_piece.color();
-        color.a = COLOR_GHOST;
-
-        let y_offset = ghost_y - self.current_piece.y;
-
-        for (x, y) in blocks {
-            if y + y_offset >= 0 {
-                let px = GRID_OFFSET_X + x as f32 * BLOCK_SIZE;
-                let py = GRID_OFFSET_Y + (y + y_offset) as f32 * BLOCK_SIZE;
-                
-                draw_rectangle(px + 1.0, py + 1.0, BLOCK_SIZE - 2.0, BLOCK_SIZE - 2.0, color);
-                draw_rectangle_lines(px, py, BLOCK_SIZE, BLOCK_SIZE, 1.0, color);
-            }
+    /// What is left to earn before the next level: lines under the fixed
+    /// goal, goal points under the variable one. `None` outside Marathon.
+    pub fn level_goal_remaining(&self) -> Option<u32> {
+        if self.rules.mode != GameMode::Marathon {
+            return None;
         }
+        Some(match self.rules.leveling {
+            Leveling::Fixed => LINES_PER_LEVEL - self.lines_cleared % LINES_PER_LEVEL,
+            Leveling::Variable => self.level * GOAL_POINTS_PER_LEVEL - self.goal_points,
+        })
     }
 
-    fn draw_current_piece(&self) {
-        let blocks = self.current_piece.get_blocks();
-        let color = self.current_piece.color();
+    fn award_clear_score(&mut self, lines: u32) {
+        // Endless Marathon has no level cap, so the products are kept in u64
+        let level = u64::from(self.level);
+        let mut points = u64::from(scoring::line_clear_score(lines, self.t_spin)) * level;
 
-        for (x, y) in blocks {
-            if y >= 0 {
-                let px = GRID_OFFSET_X + x as f32 * BLOCK_SIZE;
-                let py = GRID_OFFSET_Y + y as f32 * BLOCK_SIZE;
-                
-                draw_rectangle(px + 1.0, py + 1.0, BLOCK_SIZE - 2.0, BLOCK_SIZE - 2.0, color);
-                draw_rectangle_lines(px, py, BLOCK_SIZE, BLOCK_SIZE, 2.0, WHITE);
-            }
+        if lines == 0 {
+            // A lock without a clear breaks the combo but not back-to-back
+            self.combo = -1;
+            self.add_score(points);
+            return;
         }
-    }
 
-    fn draw_ui(&self) {
-        let ui_x = UI_OFFSET_X;
-        let mut ui_y = UI_OFFSET_Y;
-
-        // Score
-        draw_text("SCORE", ui_x, ui_y, 24.0, COLOR_TEXT);
-        ui_y += 30.0;
-        draw_text(&format!("{}", self.score), ui_x, ui_y, 32.0, WHITE);
-        ui_y += 50.0;
-
-        // High Score
-        draw_text("HIGH SCORE", ui_x, ui_y, 24.0, COLOR_TEXT);
-        ui_y += 30.0;
-        draw_text(&format!("{}", self.high_score), ui_x, ui_y, 32.0, WHITE);
-        ui_y += 50.0;
-
-        // Level
-        draw_text("LEVEL", ui_x, ui_y, 24.0, COLOR_TEXT);
-        ui_y += 30.0;
-        draw_text(&format!("{}", self.level), ui_x, ui_y, 32.0, WHITE);
-        ui_y += 50.0;
-
-        // Lines
-        draw_text("LINES", ui_x, ui_y, 24.0, COLOR_TEXT);
-        ui_y += 30.0;
-        draw_text(&format!("{}", self.lines_cleared), ui_x, ui_y, 32.0, WHITE);
-        ui_y += 50.0;
-
-        // Next piece
-        draw_text("NEXT", ui_x, ui_y, 24.0, COLOR_TEXT);
-        ui_y += 30.0;
-        self.draw_preview_piece(self.next_piece, ui_x, ui_y);
-        ui_y += 120.0;
-
-        // Hold piece
-        draw_text("HOLD", ui_x, ui_y, 24.0, COLOR_TEXT);
-        ui_y += 30.0;
-        if let Some(held_type) = self.held_piece {
-            self.draw_preview_piece(held_type, ui_x, ui_y);
-        }
-
-        // Controls
-        ui_y += 120.0;
-        draw_text("CONTROLS", ui_x, ui_y, 20.0, COLOR_TEXT);
-        ui_y += 25.0;
-        draw_text("← → Move", ui_x, ui_y, 16.0, COLOR_TEXT);
-        ui_y += 20.0;
-        draw_text("↓ Soft Drop", ui_x, ui_y, 16.0, COLOR_TEXT);
-        ui_y += 20.0;
-        draw_text("Space Hard Drop", ui_x, ui_y, 16.0, COLOR_TEXT);
-        ui_y += 20.0;
-        draw_text("Z/X Rotate", ui_x, ui_y, 16.0, COLOR_TEXT);
-        ui_y += 20.0;
-        draw_text("C Hold", ui_x, ui_y, 16.0, COLOR_TEXT);
-        ui_y += 20.0;
-        draw_text("R Restart", ui_x, ui_y, 16.0, COLOR_TEXT);
-    }
-
-    fn draw_preview_piece(&self, piece_type: TetrominoType, x: f32, y: f32) {
-        let shape = piece_type.shape();
-        let color = piece_type.color();
-        let preview_size = 20.0;
-
-        for (i, row) in shape.iter().enumerate() {
-            for (j, &cell) in row.iter().enumerate() {
-                if cell == 1 {
-                    let px = x + j as f32 * preview_size;
-                    let py = y + i as f32 * preview_size;
-                    
-                    draw_rectangle(px + 1.0, py + 1.0, preview_size - 2.0, preview_size - 2.0, color);
-                    draw_rectangle_lines(px, py, preview_size, preview_size, 1.0, WHITE);
-                }
+        let was_back_to_back = self.back_to_back > 0;
+        if scoring::is_difficult(lines, self.t_spin) {
+            if was_back_to_back {
+                points = scoring::back_to_back_bonus(points);
             }
+            self.back_to_back += 1;
+        } else {
+            self.back_to_back = 0;
+        }
+
+        if self.is_board_empty() {
+            self.perfect_clears += 1;
+            points += u64::from(scoring::perfect_clear_bonus(lines, was_back_to_back)) * level;
         }
+
+        self.combo += 1;
+        points += u64::from(SCORE_COMBO) * self.combo as u64 * level;
+
+        self.add_score(points);
+    }
+
+    fn add_score(&mut self, points: u64) {
+        self.score = self.score.saturating_add(points);
     }
 
-    fn draw_game_over(&self) {
-        let overlay_color = Color::new(0.0, 0.0, 0.0, 0.7);
-        draw_rectangle(0.0, 0.0, SCREEN_WIDTH, SCREEN_HEIGHT, overlay_color);
+    /// Definition of a piece of the game's piece set.
+    pub fn piece_def(&self, id: PieceId) -> &PieceDef {
+        self.rules.piece_set.get(id)
+    }
 
-        let text = "GAME OVER";
-        let text_size = 48.0;
-        let text_dims = measure_text(text, None, text_size as u16, 1.0);
-        draw_text(
-            text,
-            (SCREEN_WIDTH - text_dims.width) / 2.0,
-            SCREEN_HEIGHT / 2.0 - 50.0,
-            text_size,
-            WHITE,
-        );
+    /// Board columns.
+    pub fn width(&self) -> usize {
+        self.rules.width
+    }
 
-        let restart_text = "Press R to Restart";
-        let restart_size = 24.0;
-        let restart_dims = measure_text(restart_text, None, restart_size as u16, 1.0);
-        draw_text(
-            restart_text,
-            (SCREEN_WIDTH - restart_dims.width) / 2.0,
-            SCREEN_HEIGHT / 2.0 + 20.0,
-            restart_size,
-            COLOR_TEXT,
-        );
+    /// Visible board rows.
+    pub fn height(&self) -> usize {
+        self.rules.height
     }
 
-    fn load_high_score() -> u32 {
-        fs::read_to_string(HIGHSCORE_FILE)
-            .ok()
-            .and_then(|s| s.trim().parse().ok())
-            .unwrap_or(0)
+    /// Visible rows plus the hidden vanish zone.
+    pub fn total_height(&self) -> usize {
+        self.rules.height + BUFFER_HEIGHT
     }
 
-    fn save_high_score(score: u32) {
-        let _ = fs::write(HIGHSCORE_FILE, score.to_string());
+    pub fn is_board_empty(&self) -> bool {
+        self.grid.iter().all(|row| row.iter().all(|cell| cell.is_none()))
     }
 
     pub fn reset(&mut self) {
-        *self = GameState::new();
+        *self = GameState::with_rules(self.rules.clone());
+    }
+}
+
+impl Default for GameState {
+    fn default() -> Self {
+        Self::new()
     }
 }
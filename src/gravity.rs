@@ -0,0 +1,22 @@
+//! Guideline gravity curve.
+
+use crate::constants::*;
+
+/// Rows the piece falls per frame at `level`, capped at 20G.
+///
+/// The guideline gives the time per row in seconds as
+/// `(0.8 - (level - 1) * 0.007) ^ (level - 1)`.
+pub fn rows_per_frame(level: u32) -> f32 {
+    let steps = level.saturating_sub(1) as f32;
+    let seconds_per_row = (0.8 - steps * 0.007).max(0.0).powf(steps);
+    if seconds_per_row <= 0.0 {
+        return MAX_GRAVITY;
+    }
+
+    (1.0 / (seconds_per_row * FRAMES_PER_SECOND)).min(MAX_GRAVITY)
+}
+
+/// Soft drop falls `SOFT_DROP_FACTOR` times faster than normal gravity.
+pub fn soft_drop_rows_per_frame(level: u32) -> f32 {
+    (rows_per_frame(level) * SOFT_DROP_FACTOR).min(MAX_GRAVITY)
+}
@@ -0,0 +1,19 @@
+//! Headless Tetris engine.
+//!
+//! Everything in this crate is renderer-agnostic: the grid, pieces, randomizer
+//! and scoring rules can be driven by a bot, a test or a tool without opening
+//! a window. The macroquad frontend lives in the `tetris` binary.
+
+pub mod constants;
+pub mod rng;
+pub mod pieces;
+pub mod tetromino;
+pub mod randomizer;
+pub mod srs;
+pub mod scoring;
+pub mod gravity;
+pub mod rules;
+pub mod game;
+pub mod autoshift;
+pub mod replay;
+pub mod replay_file;
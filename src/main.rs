@@ -1,10 +1,23 @@
-mod constants;
-mod tetromino;
-mod game;
+mod frontend;
 
 use macroquad::prelude::*;
-use constants::*;
-use game::GameState;
+use frontend::constants::*;
+use frontend::banner::Banner;
+use frontend::focus::Focus;
+use frontend::game_select::GameSelect;
+use frontend::input::TickInput;
+use frontend::layout::Layout;
+use frontend::settings::Settings;
+use frontend::viewer::ReplayViewer;
+use frontend::highscore::Records;
+use frontend::{render, replays, savegame};
+use std::process::ExitCode;
+use tetris::autoshift::{AutoShift, Direction};
+use tetris::constants::TICK_DURATION;
+use tetris::game::GameState;
+use tetris::replay::Action;
+use tetris::replay_file::ReplayFile;
+use tetris::rules::{GameMode, Rules};
 
 fn window_conf() -> Conf {
     Conf {
@@ -12,73 +25,293 @@ fn window_conf() -> Conf {
         window_width: SCREEN_WIDTH as i32,
         window_height: SCREEN_HEIGHT as i32,
         window_resizable: false,
+        platform: miniquad::conf::Platform {
+            linux_wm_class: WM_CLASS,
+            ..Default::default()
+        },
         ..Default::default()
     }
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
-    let mut game_state = GameState::new();
-    let mut last_move_time = 0.0;
-    let move_delay = 0.15; // Delay between moves in seconds
+/// `--seed <n>` deals the same piece sequence in every game, restarts included.
+fn seed_from_args() -> Option<u64> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            return args.next().and_then(|value| value.parse().ok());
+        }
+    }
+    None
+}
+
+fn new_game(rules: &Rules, seed: Option<u64>) -> GameState {
+    match seed {
+        Some(seed) => GameState::with_seed(rules.clone(), seed),
+        None => GameState::with_rules(rules.clone()),
+    }
+}
+
+/// Advances the game one tick, applying the input first.
+fn run_tick(game_state: &mut GameState, auto_shift: &mut AutoShift, input: &TickInput) {
+    // Keep charging DAS even while input is blocked
+    let shift = auto_shift.update(
+        input.left_down,
+        input.right_down,
+        input.left_pressed,
+        input.right_pressed,
+        TICK_DURATION,
+    );
+
+    // Movement; the engine ignores actions during the line clear animation
+    if let Some(shift) = shift {
+        let action = match shift.direction {
+            Direction::Left => Action::MoveLeft,
+            Direction::Right => Action::MoveRight,
+        };
+        for _ in 0..shift.cells {
+            if !game_state.apply(action) {
+                break;
+            }
+        }
+    }
+
+    // Rotation
+    if input.rotate_cw {
+        game_state.apply(Action::RotateCw);
+    }
+    if input.rotate_ccw {
+        game_state.apply(Action::RotateCcw);
+    }
+    if input.rotate_180 {
+        game_state.apply(Action::Rotate180);
+    }
+
+    // Hard drop
+    if input.hard_drop {
+        game_state.apply(Action::HardDrop);
+    }
+
+    // Hold
+    if input.hold {
+        game_state.apply(Action::Hold);
+    }
+
+    // Soft drop
+    game_state.apply(Action::SoftDrop(input.soft_drop));
+
+    game_state.tick();
+}
+
+/// `tetris verify <file>...` plays replays back headlessly and checks their scores.
+fn verify_replays(paths: &[String]) -> ExitCode {
+    if paths.is_empty() {
+        eprintln!("usage: tetris verify <replay file>...");
+        return ExitCode::FAILURE;
+    }
+
+    let mut all_verified = true;
+    for path in paths {
+        match ReplayFile::load(path).and_then(|file| file.verify().map(|_| file)) {
+            Ok(file) => println!(
+                "{}: OK, {} scored {} with {} lines",
+                path, file.player, file.score, file.lines
+            ),
+            Err(err) => {
+                println!("{}: FAILED, {}", path, err);
+                all_verified = false;
+            }
+        }
+    }
+
+    if all_verified {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// `tetris watch <file>` opens a saved or shared replay in the viewer.
+fn watch_replay(paths: &[String]) -> ExitCode {
+    let [path] = paths else {
+        eprintln!("usage: tetris watch <replay file>");
+        return ExitCode::FAILURE;
+    };
+
+    match ReplayFile::load(path) {
+        Ok(file) => {
+            let conf = Conf {
+                window_title: format!("Tetris - {}", file.player),
+                ..window_conf()
+            };
+            macroquad::Window::from_config(conf, watch(file));
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("verify") => return verify_replays(&args[1..]),
+        Some("watch") => return watch_replay(&args[1..]),
+        _ => {}
+    }
+
+    macroquad::Window::from_config(window_conf(), run());
+    ExitCode::SUCCESS
+}
+
+/// Plays a replay file back until the viewer is left or the window closed.
+async fn watch(file: ReplayFile) {
+    let records = Records::load();
+    let mut viewer = ReplayViewer::new(file.replay);
+
+    loop {
+        viewer.update(get_frame_time());
+        render::draw(viewer.game(), &records);
+        viewer.draw();
+        if is_key_pressed(KeyCode::V) || is_key_pressed(KeyCode::Escape) {
+            break;
+        }
+        next_frame().await;
+    }
+}
+
+async fn run() {
+    let settings = Settings::load();
+    let seed = seed_from_args();
+    let mut game_select = GameSelect::new(&settings);
+    let mut game_state = new_game(&game_select.rules(&settings), seed);
+    let mut records = Records::load();
+    let mut banner = Banner::new();
+    let mut perfect_clears = 0;
+    let mut auto_shift = AutoShift::new(settings.das, settings.arr);
+    let mut input = TickInput::default();
+    let mut tick_time = 0.0;
+    let mut viewer: Option<ReplayViewer> = None;
+    let mut saved_game = savegame::load_game();
+    let mut focus = Focus::new();
+
+    // Closing the window saves the game like Escape does
+    prevent_quit();
 
     loop {
         let delta_time = get_frame_time();
-        let current_time = get_time();
-
-        // Handle input
-        if !game_state.game_over {
-            // Block input during line clear animation
-            if game_state.state == game::State::Playing {
-                // Movement
-                if is_key_down(KeyCode::Left) && current_time - last_move_time > move_delay {
-                    game_state.move_left();
-                    last_move_time = current_time;
-                }
-                if is_key_down(KeyCode::Right) && current_time - last_move_time > move_delay {
-                    game_state.move_right();
-                    last_move_time = current_time;
-                }
-
-                // Rotation
-                if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::X) {
-                    game_state.rotate_cw();
-                }
-                if is_key_pressed(KeyCode::Z) {
-                    game_state.rotate_ccw();
-                }
-
-                // Hard drop
-                if is_key_pressed(KeyCode::Space) {
-                    game_state.hard_drop();
-                }
-
-                // Hold
-                if is_key_pressed(KeyCode::C) {
-                    game_state.hold_piece();
-                }
-
-                // Soft drop
-                let soft_drop = is_key_down(KeyCode::Down);
-                game_state.update(delta_time, soft_drop);
+
+        // Offer to continue the game saved on the last quit
+        if let Some(saved) = &saved_game {
+            render::draw_continue_prompt(saved);
+            if is_key_pressed(KeyCode::C) {
+                game_state = saved_game.take().unwrap();
+                perfect_clears = game_state.perfect_clears;
+                savegame::discard_game();
+            } else if is_key_pressed(KeyCode::N) {
+                saved_game = None;
+                savegame::discard_game();
+            } else if is_key_pressed(KeyCode::Escape) || is_quit_requested() {
+                break;
+            }
+            next_frame().await;
+            continue;
+        }
+
+        // Watching a replay of the last game
+        if let Some(replay_viewer) = viewer.as_mut() {
+            replay_viewer.update(delta_time);
+            render::draw(replay_viewer.game(), &records);
+            replay_viewer.draw();
+            if is_key_pressed(KeyCode::V) || is_key_pressed(KeyCode::Escape) {
+                viewer = None;
+            }
+            if is_quit_requested() {
+                break;
+            }
+            next_frame().await;
+            continue;
+        }
+        let was_over = game_state.is_over();
+
+        // Pause on request, when the window loses focus, or when it stopped drawing for a while
+        if is_key_pressed(KeyCode::P) {
+            if game_state.is_paused() {
+                game_state.resume();
             } else {
-                // During animation, just update without input
-                game_state.update(delta_time, false);
+                game_state.pause();
+            }
+        }
+        if focus.lost(delta_time) || delta_time > AUTO_PAUSE_FRAME_TIME {
+            game_state.pause();
+        }
+
+        // Run as many fixed ticks as the frame took; nothing runs while paused
+        input.poll();
+        if game_state.is_paused() {
+            input = TickInput::default();
+        } else {
+            tick_time += delta_time.min(MAX_FRAME_TIME);
+        }
+        while tick_time >= TICK_DURATION {
+            tick_time -= TICK_DURATION;
+            let tick_input = input.take();
+            if !game_state.is_over() {
+                run_tick(&mut game_state, &mut auto_shift, &tick_input);
             }
         }
 
-        // Restart
+        // Announce perfect clears
+        if game_state.perfect_clears > perfect_clears {
+            banner.show("PERFECT CLEAR");
+        }
+        perfect_clears = game_state.perfect_clears;
+        banner.update(delta_time);
+
+        // Track the high score live and save records when the game ends
+        if game_state.rules.mode == GameMode::Marathon && game_state.score > records.high_score {
+            records.high_score = game_state.score;
+        }
+        if game_state.is_over() && !was_over {
+            records.record(&game_state, &settings.player_name);
+        }
+
+        // Pick the next game's mode between games, then restart into it
+        if game_state.is_over() || game_state.is_paused() {
+            game_select.update();
+        }
         if is_key_pressed(KeyCode::R) {
-            game_state.reset();
+            game_state = new_game(&game_select.rules(&settings), seed);
+            input = TickInput::default();
         }
 
-        // Quit
-        if is_key_pressed(KeyCode::Escape) {
+        // Watch or save the finished game
+        if game_state.is_over() && is_key_pressed(KeyCode::V) {
+            viewer = Some(ReplayViewer::new(game_state.replay()));
+        }
+        if game_state.is_over() && is_key_pressed(KeyCode::S) {
+            match replays::save_replay(&game_state, &settings.player_name) {
+                Ok(_) => banner.show("REPLAY SAVED"),
+                Err(_) => banner.show("SAVE FAILED"),
+            }
+        }
+
+        // Quit, saving an unfinished game to continue next time
+        let quit_from_menu = game_state.is_paused() && is_key_pressed(KeyCode::Q);
+        if is_key_pressed(KeyCode::Escape) || is_quit_requested() || quit_from_menu {
+            if !game_state.is_over() {
+                savegame::save_game(&game_state, &settings.player_name);
+            }
             break;
         }
 
         // Draw everything
-        game_state.draw();
+        render::draw(&game_state, &records);
+        if game_state.is_over() || game_state.is_paused() {
+            game_select.draw();
+        }
+        banner.draw(&Layout::new(game_state.width(), game_state.height()));
 
         next_frame().await;
     }
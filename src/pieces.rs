@@ -0,0 +1,307 @@
+//! Piece definitions and the text files they are loaded from.
+//!
+//! A piece set file lists pieces one after another:
+//!
+//! ```text
+//! # Comments start with '#'
+//! set Standard
+//!
+//! piece T
+//! color 0.7 0.0 0.9
+//! spawn 0 0
+//! kicks srs
+//! shape
+//! .X.
+//! XXX
+//! ...
+//! ```
+//!
+//! A single `shape` is the spawn state and must be square; the other three
+//! states are made by turning it about the center of its box. Four `shape`
+//! blocks give the spawn, R, 2 and L states explicitly. `spawn` moves the
+//! piece from its centered spawn position (+y is down). `kicks` picks a
+//! built-in table (`srs`, `srs_i` or `none`, the default) and
+//! `kick <from> <to> x,y ...` overrides one transition, with states written
+//! as 0, R, 2 and L and offsets y-up as in the published SRS tables.
+
+use crate::srs::{Kick, KickTable};
+use std::fs;
+
+// Largest spawn or kick offset in either direction, well past any board edge.
+// Piece sets also arrive inside replay files, so this keeps untrusted
+// offsets from overflowing piece positions.
+const MAX_OFFSET: i32 = 100;
+
+/// Index of a piece in its `PieceSet`.
+pub type PieceId = usize;
+
+#[derive(Debug, Clone)]
+pub struct PieceDef {
+    pub name: String,
+    /// Red, green and blue, 0.0 to 1.0
+    pub color: [f32; 3],
+    /// Cells of each rotation state: spawn, R, 2, L
+    pub rotations: [Vec<Vec<u8>>; 4],
+    /// Offset from the centered spawn position, +y down
+    pub spawn_offset: (i32, i32),
+    pub kicks: KickTable,
+}
+
+impl PieceDef {
+    /// Number of cells the piece covers.
+    pub fn cell_count(&self) -> usize {
+        self.rotations[0].iter().flatten().filter(|&&cell| cell == 1).count()
+    }
+}
+
+/// The pieces a game is played with.
+#[derive(Debug, Clone)]
+pub struct PieceSet {
+    pub name: String,
+    /// The text the set was parsed from
+    pub source: String,
+    pieces: Vec<PieceDef>,
+}
+
+impl PieceSet {
+    /// The seven guideline tetrominoes.
+    pub fn standard() -> Self {
+        Self::parse(include_str!("../pieces/standard.txt")).expect("built-in piece set is valid")
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+        Self::parse(&contents).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut name = String::from("Custom");
+        let mut pieces = Vec::new();
+        let mut current: Option<PieceBuilder> = None;
+        let mut in_shape = false;
+
+        for (index, raw_line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            let error = |message: &str| format!("line {}: {}", line_number, message);
+
+            if line.is_empty() {
+                in_shape = false;
+                continue;
+            }
+
+            // Shape rows follow a `shape` line until the next keyword
+            if in_shape && line.chars().all(|c| c == 'X' || c == '.') {
+                let piece = current.as_mut().unwrap();
+                let row = line.chars().map(|c| u8::from(c == 'X')).collect();
+                piece.shapes.last_mut().unwrap().push(row);
+                continue;
+            }
+            in_shape = false;
+
+            let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+
+            if keyword == "set" {
+                name = rest.to_string();
+                continue;
+            }
+            if keyword == "piece" {
+                if rest.is_empty() {
+                    return Err(error("piece needs a name"));
+                }
+                if let Some(piece) = current.take() {
+                    pieces.push(piece.build()?);
+                }
+                current = Some(PieceBuilder::new(rest, line_number));
+                continue;
+            }
+
+            let Some(piece) = current.as_mut() else {
+                return Err(error("expected `piece <name>` first"));
+            };
+            match keyword {
+                "color" => {
+                    let values = parse_numbers::<f32>(rest).ok_or_else(|| error("bad color"))?;
+                    let [r, g, b] = values[..] else {
+                        return Err(error("color needs three values"));
+                    };
+                    piece.color = [r, g, b];
+                }
+                "spawn" => {
+                    let values = parse_numbers::<i32>(rest).ok_or_else(|| error("bad spawn offset"))?;
+                    let [dx, dy] = values[..] else {
+                        return Err(error("spawn needs two values"));
+                    };
+                    if dx.abs() > MAX_OFFSET || dy.abs() > MAX_OFFSET {
+                        return Err(error("spawn offset out of range"));
+                    }
+                    piece.spawn_offset = (dx, dy);
+                }
+                "kicks" => {
+                    piece.kicks = match rest {
+                        "srs" => KickTable::jlstz(),
+                        "srs_i" => KickTable::i(),
+                        "none" => KickTable::none(),
+                        _ => return Err(error("kicks must be srs, srs_i or none")),
+                    };
+                }
+                "kick" => {
+                    let mut parts = rest.split_whitespace();
+                    let from = parts.next().and_then(parse_state);
+                    let to = parts.next().and_then(parse_state);
+                    let (Some(from), Some(to)) = (from, to) else {
+                        return Err(error("kick needs two states out of 0, R, 2 and L"));
+                    };
+                    let kicks = parts
+                        .map(parse_kick)
+                        .collect::<Option<Vec<Kick>>>()
+                        .ok_or_else(|| error(&format!("kick offsets are written x,y, at most {} either way", MAX_OFFSET)))?;
+                    piece.kicks.set(from, to, kicks);
+                }
+                "shape" => {
+                    piece.shapes.push(Vec::new());
+                    in_shape = true;
+                }
+                _ => return Err(error(&format!("unknown keyword `{}`", keyword))),
+            }
+        }
+
+        if let Some(piece) = current.take() {
+            pieces.push(piece.build()?);
+        }
+        if pieces.is_empty() {
+            return Err("no pieces defined".to_string());
+        }
+        for (i, piece) in pieces.iter().enumerate() {
+            if pieces[..i].iter().any(|other| other.name == piece.name) {
+                return Err(format!("piece `{}` is defined twice", piece.name));
+            }
+        }
+
+        Ok(PieceSet {
+            name,
+            source: text.to_string(),
+            pieces,
+        })
+    }
+
+    pub fn get(&self, id: PieceId) -> &PieceDef {
+        &self.pieces[id]
+    }
+
+    /// Looks a piece up by name.
+    pub fn find(&self, name: &str) -> Option<PieceId> {
+        self.pieces.iter().position(|piece| piece.name == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pieces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pieces.is_empty()
+    }
+
+    /// Every piece id, in file order.
+    pub fn ids(&self) -> std::ops::Range<PieceId> {
+        0..self.pieces.len()
+    }
+}
+
+impl Default for PieceSet {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// A piece whose definition is still being read.
+struct PieceBuilder {
+    name: String,
+    line_number: usize,
+    color: [f32; 3],
+    spawn_offset: (i32, i32),
+    kicks: KickTable,
+    shapes: Vec<Vec<Vec<u8>>>,
+}
+
+impl PieceBuilder {
+    fn new(name: &str, line_number: usize) -> Self {
+        PieceBuilder {
+            name: name.to_string(),
+            line_number,
+            color: [0.8, 0.8, 0.8],
+            spawn_offset: (0, 0),
+            kicks: KickTable::none(),
+            shapes: Vec::new(),
+        }
+    }
+
+    fn build(self) -> Result<PieceDef, String> {
+        let error = |message: &str| format!("piece `{}` (line {}): {}", self.name, self.line_number, message);
+
+        for shape in &self.shapes {
+            let width = shape.first().map_or(0, Vec::len);
+            if shape.iter().any(|row| row.len() != width) {
+                return Err(error("shape rows must all be the same length"));
+            }
+            if !shape.iter().flatten().any(|&cell| cell == 1) {
+                return Err(error("shape has no cells"));
+            }
+        }
+
+        let rotations = match &self.shapes[..] {
+            [spawn] => {
+                if spawn.iter().any(|row| row.len() != spawn.len()) {
+                    return Err(error("a single shape must be square"));
+                }
+                let right = rotate_cw(spawn);
+                let flipped = rotate_cw(&right);
+                let left = rotate_cw(&flipped);
+                [spawn.clone(), right, flipped, left]
+            }
+            [spawn, right, flipped, left] => [spawn.clone(), right.clone(), flipped.clone(), left.clone()],
+            _ => return Err(error("needs one shape or four")),
+        };
+
+        Ok(PieceDef {
+            name: self.name,
+            color: self.color,
+            rotations,
+            spawn_offset: self.spawn_offset,
+            kicks: self.kicks,
+        })
+    }
+}
+
+/// Turns a square matrix a quarter turn clockwise.
+fn rotate_cw(shape: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let n = shape.len();
+    let mut rotated = vec![vec![0; n]; n];
+    for (i, row) in shape.iter().enumerate() {
+        for (j, &cell) in row.iter().enumerate() {
+            rotated[j][n - 1 - i] = cell;
+        }
+    }
+    rotated
+}
+
+fn parse_numbers<T: std::str::FromStr>(text: &str) -> Option<Vec<T>> {
+    text.split_whitespace().map(|value| value.parse().ok()).collect()
+}
+
+fn parse_state(text: &str) -> Option<u8> {
+    match text {
+        "0" => Some(0),
+        "R" => Some(1),
+        "2" => Some(2),
+        "L" => Some(3),
+        _ => None,
+    }
+}
+
+fn parse_kick(text: &str) -> Option<Kick> {
+    let (x, y) = text.split_once(',')?;
+    let kick: Kick = (x.trim().parse().ok()?, y.trim().parse().ok()?);
+    (kick.0.abs() <= MAX_OFFSET && kick.1.abs() <= MAX_OFFSET).then_some(kick)
+}
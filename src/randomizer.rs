@@ -0,0 +1,230 @@
+//! Piece randomizers.
+//!
+//! Every generator is driven by a seeded `Rng`, so the same kind and seed
+//! always deal the same sequence.
+
+use crate::pieces::{PieceId, PieceSet};
+use crate::rng::Rng;
+use std::collections::VecDeque;
+
+pub trait Randomizer {
+    /// Produces the next piece of the sequence.
+    fn generate(&mut self) -> PieceId;
+}
+
+/// The randomizer algorithms a game can be played with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandomizerKind {
+    /// Shuffled bags holding one of each piece (seven with the standard set)
+    Bag7,
+    /// Shuffled bags holding two of each piece
+    Bag14,
+    /// Every piece equally likely, with no memory
+    Memoryless,
+    /// NES: reroll once when the piece repeats the previous one
+    Nes,
+    /// TGM2: up to 6 rolls to avoid the last 4 pieces
+    Tgm,
+}
+
+impl RandomizerKind {
+    /// Name used in settings and replay files.
+    pub fn name(self) -> &'static str {
+        match self {
+            RandomizerKind::Bag7 => "bag7",
+            RandomizerKind::Bag14 => "bag14",
+            RandomizerKind::Memoryless => "random",
+            RandomizerKind::Nes => "nes",
+            RandomizerKind::Tgm => "tgm",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bag7" => Some(RandomizerKind::Bag7),
+            "bag14" => Some(RandomizerKind::Bag14),
+            "random" => Some(RandomizerKind::Memoryless),
+            "nes" => Some(RandomizerKind::Nes),
+            "tgm" => Some(RandomizerKind::Tgm),
+            _ => None,
+        }
+    }
+
+    /// A generator dealing the pieces of `pieces`.
+    pub fn create(self, pieces: &PieceSet, seed: u64) -> Box<dyn Randomizer> {
+        let rng = Rng::new(seed);
+        let count = pieces.len();
+        match self {
+            RandomizerKind::Bag7 => Box::new(BagRandomizer::new(rng, count, 1)),
+            RandomizerKind::Bag14 => Box::new(BagRandomizer::new(rng, count, 2)),
+            RandomizerKind::Memoryless => Box::new(MemorylessRandomizer { rng, count }),
+            RandomizerKind::Nes => Box::new(NesRandomizer { rng, count, last: None }),
+            RandomizerKind::Tgm => Box::new(TgmRandomizer::new(rng, pieces)),
+        }
+    }
+}
+
+// Bag Randomizer
+pub struct BagRandomizer {
+    bag: Vec<PieceId>,
+    count: usize,
+    copies: usize,
+    rng: Rng,
+}
+
+impl BagRandomizer {
+    /// Each bag holds `copies` of each of the `count` pieces.
+    pub fn new(rng: Rng, count: usize, copies: usize) -> Self {
+        BagRandomizer {
+            bag: Vec::new(),
+            count: count.max(1),
+            copies: copies.max(1),
+            rng,
+        }
+    }
+
+    fn refill_bag(&mut self) {
+        for _ in 0..self.copies {
+            self.bag.extend(0..self.count);
+        }
+        self.rng.shuffle(&mut self.bag);
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn generate(&mut self) -> PieceId {
+        if self.bag.is_empty() {
+            self.refill_bag();
+        }
+        self.bag.pop().unwrap()
+    }
+}
+
+// Memoryless Randomizer
+pub struct MemorylessRandomizer {
+    rng: Rng,
+    count: usize,
+}
+
+impl Randomizer for MemorylessRandomizer {
+    fn generate(&mut self) -> PieceId {
+        self.rng.below(self.count)
+    }
+}
+
+// NES Randomizer
+pub struct NesRandomizer {
+    rng: Rng,
+    count: usize,
+    last: Option<PieceId>,
+}
+
+impl Randomizer for NesRandomizer {
+    fn generate(&mut self) -> PieceId {
+        // The first roll has an extra "reroll" outcome past the last piece
+        let roll = self.rng.below(self.count + 1);
+        let piece = if roll < self.count && Some(roll) != self.last {
+            roll
+        } else {
+            self.rng.below(self.count)
+        };
+
+        self.last = Some(piece);
+        piece
+    }
+}
+
+// TGM Randomizer
+pub struct TgmRandomizer {
+    rng: Rng,
+    count: usize,
+    /// Pieces the first piece is drawn from
+    openers: Vec<PieceId>,
+    history: VecDeque<Option<PieceId>>,
+    first: bool,
+}
+
+impl TgmRandomizer {
+    const ROLLS: usize = 6;
+
+    /// The history starts as Z, S, S, Z and the first piece is never S, Z
+    /// or O, as far as `pieces` has pieces by those names.
+    pub fn new(rng: Rng, pieces: &PieceSet) -> Self {
+        let (s, z, o) = (pieces.find("S"), pieces.find("Z"), pieces.find("O"));
+        let mut openers: Vec<PieceId> = pieces
+            .ids()
+            .filter(|&id| ![s, z, o].contains(&Some(id)))
+            .collect();
+        if openers.is_empty() {
+            openers = pieces.ids().collect();
+        }
+        TgmRandomizer {
+            rng,
+            count: pieces.len(),
+            openers,
+            history: VecDeque::from([z, s, s, z]),
+            first: true,
+        }
+    }
+}
+
+impl Randomizer for TgmRandomizer {
+    fn generate(&mut self) -> PieceId {
+        let piece = if self.first {
+            self.first = false;
+            self.openers[self.rng.below(self.openers.len())]
+        } else {
+            let mut piece = self.rng.below(self.count);
+            for _ in 1..Self::ROLLS {
+                if !self.history.contains(&Some(piece)) {
+                    break;
+                }
+                piece = self.rng.below(self.count);
+            }
+            piece
+        };
+
+        self.history.pop_front();
+        self.history.push_back(Some(piece));
+        piece
+    }
+}
+
+/// Buffers a randomizer's output so the next queue can look ahead.
+pub struct PieceQueue {
+    randomizer: Box<dyn Randomizer>,
+    queue: VecDeque<PieceId>,
+}
+
+impl PieceQueue {
+    pub fn new(kind: RandomizerKind, pieces: &PieceSet, seed: u64) -> Self {
+        PieceQueue {
+            randomizer: kind.create(pieces, seed),
+            queue: VecDeque::new(),
+        }
+    }
+
+    fn fill_to(&mut self, count: usize) {
+        while self.queue.len() < count {
+            let piece = self.randomizer.generate();
+            self.queue.push_back(piece);
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> PieceId {
+        self.fill_to(1);
+        self.queue.pop_front().unwrap()
+    }
+
+    pub fn peek(&mut self) -> PieceId {
+        self.fill_to(1);
+        self.queue[0]
+    }
+
+    /// The next `count` pieces.
+    pub fn preview(&mut self, count: usize) -> Vec<PieceId> {
+        self.fill_to(count);
+        self.queue.iter().take(count).copied().collect()
+    }
+}
@@ -0,0 +1,118 @@
+//! Input recording and deterministic playback.
+//!
+//! A game is fully determined by its rules, its seed and the actions made
+//! through `GameState::apply`, so a replay stores only those and plays the
+//! game back through the same engine code.
+
+use crate::game::GameState;
+use crate::rules::Rules;
+
+/// Something the player did to the falling piece.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    RotateCw,
+    RotateCcw,
+    Rotate180,
+    HardDrop,
+    Hold,
+    /// Soft drop pressed (`true`) or released (`false`)
+    SoftDrop(bool),
+}
+
+/// An action and the tick it was made on, before that tick ran.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Input {
+    pub tick: u64,
+    pub action: Action,
+}
+
+/// A recorded game.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub rules: Rules,
+    pub seed: u64,
+    /// Recorded actions in the order they were made
+    pub inputs: Vec<Input>,
+    /// Length of the game in ticks
+    pub ticks: u64,
+}
+
+/// Plays a replay back one tick at a time.
+pub struct ReplayPlayer {
+    replay: Replay,
+    game: GameState,
+    next_input: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let game = GameState::with_seed(replay.rules.clone(), replay.seed);
+        ReplayPlayer {
+            replay,
+            game,
+            next_input: 0,
+        }
+    }
+
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Ticks played back so far.
+    pub fn tick(&self) -> u64 {
+        self.game.ticks
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let input_pending = self
+            .replay
+            .inputs
+            .get(self.next_input)
+            .is_some_and(|input| input.tick <= self.game.ticks);
+        self.game.is_over() || (self.game.ticks >= self.replay.ticks && !input_pending)
+    }
+
+    /// Applies the actions recorded for the current tick, then runs it.
+    pub fn step(&mut self) {
+        while let Some(input) = self.replay.inputs.get(self.next_input) {
+            if input.tick > self.game.ticks {
+                break;
+            }
+            self.game.apply(input.action);
+            self.next_input += 1;
+        }
+
+        if self.game.ticks < self.replay.ticks {
+            self.game.tick();
+        }
+    }
+
+    /// Moves playback to `tick`. Going backwards replays from the start.
+    pub fn seek(&mut self, tick: u64) {
+        if tick < self.game.ticks {
+            self.game = GameState::with_seed(self.replay.rules.clone(), self.replay.seed);
+            self.next_input = 0;
+        }
+        while self.game.ticks < tick && !self.is_finished() {
+            self.step();
+        }
+    }
+
+    /// Plays the rest of the replay in one go.
+    pub fn run_to_end(&mut self) {
+        while !self.is_finished() {
+            self.step();
+        }
+    }
+
+    /// The game as far as it has been played back, ready to continue.
+    pub fn into_game(self) -> GameState {
+        self.game
+    }
+}
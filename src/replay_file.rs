@@ -0,0 +1,341 @@
+//! On-disk replay format and score verification.
+//!
+//! Replays are plain text:
+//!
+//! ```text
+//! format 1
+//! engine 0.1.0
+//! player Anh
+//! seed 42
+//! mode marathon
+//! start_level 1
+//! marathon_goal endless
+//! leveling fixed
+//! width 10
+//! height 20
+//! lock_mode move
+//! preview_count 5
+//! randomizer bag7
+//! pieces 59
+//! <the 59 lines of the piece set file>
+//! ticks 3600
+//! score 12400
+//! lines 31
+//! inputs 4
+//! 12L 0C 30D 2S
+//! checksum 5f0c2a9b81d3e476
+//! ```
+//!
+//! Each input is the number of ticks since the previous one followed by an
+//! action code: `L`/`R` move, `C`/`A`/`F` rotate clockwise, anticlockwise and
+//! 180, `D` hard drop, `H` hold, `S`/`s` soft drop pressed/released. The
+//! checksum is a 64-bit FNV-1a hash of every line before the checksum line,
+//! each ended with `\n`.
+
+use crate::game::GameState;
+use crate::pieces::PieceSet;
+use crate::randomizer::RandomizerKind;
+use crate::replay::{Action, Input, Replay, ReplayPlayer};
+use crate::rules::{marathon_goal_name, parse_marathon_goal, GameMode, Leveling, LockMode, Rules};
+use std::fmt::Write;
+use std::fs;
+use std::sync::Arc;
+
+/// Version of the file layout, bumped on incompatible changes.
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+/// Version of the engine, which must match for a replay to play back the same.
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Longest replay that is played back, a day of play. Longer claims are
+/// rejected so a crafted file can't keep `verify` running forever.
+pub const MAX_REPLAY_TICKS: u64 = 24 * 60 * 60 * 60;
+
+// Inputs written per line
+const INPUTS_PER_LINE: usize = 16;
+
+// Shortest input token, a one-digit delta and a code
+const MIN_INPUT_LENGTH: usize = 2;
+
+/// A replay with the result it claims.
+#[derive(Debug, Clone)]
+pub struct ReplayFile {
+    pub engine_version: String,
+    pub player: String,
+    pub replay: Replay,
+    pub score: u64,
+    pub lines: u32,
+}
+
+impl ReplayFile {
+    /// The replay of `game` so far, claiming its current score and lines.
+    pub fn from_game(game: &GameState, player: &str) -> Self {
+        ReplayFile {
+            engine_version: ENGINE_VERSION.to_string(),
+            player: player.to_string(),
+            replay: game.replay(),
+            score: game.score,
+            lines: game.lines_cleared,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let rules = &self.replay.rules;
+        let mut text = String::new();
+        // Writing to a String cannot fail
+        let _ = writeln!(text, "format {}", REPLAY_FORMAT_VERSION);
+        let _ = writeln!(text, "engine {}", self.engine_version);
+        let _ = writeln!(text, "player {}", self.player);
+        let _ = writeln!(text, "seed {}", self.replay.seed);
+        let _ = writeln!(text, "mode {}", rules.mode.name());
+        let _ = writeln!(text, "start_level {}", rules.start_level);
+        let _ = writeln!(text, "marathon_goal {}", marathon_goal_name(rules.marathon_goal));
+        let _ = writeln!(text, "leveling {}", rules.leveling.name());
+        let _ = writeln!(text, "width {}", rules.width);
+        let _ = writeln!(text, "height {}", rules.height);
+        let _ = writeln!(text, "lock_mode {}", rules.lock_mode.name());
+        let _ = writeln!(text, "preview_count {}", rules.preview_count);
+        let _ = writeln!(text, "randomizer {}", rules.randomizer.name());
+        let _ = writeln!(text, "pieces {}", rules.piece_set.source.lines().count());
+        for line in rules.piece_set.source.lines() {
+            let _ = writeln!(text, "{}", line);
+        }
+        let _ = writeln!(text, "ticks {}", self.replay.ticks);
+        let _ = writeln!(text, "score {}", self.score);
+        let _ = writeln!(text, "lines {}", self.lines);
+
+        let _ = writeln!(text, "inputs {}", self.replay.inputs.len());
+        let mut last_tick = 0;
+        for chunk in self.replay.inputs.chunks(INPUTS_PER_LINE) {
+            let tokens: Vec<String> = chunk
+                .iter()
+                .map(|input| {
+                    let delta = input.tick - last_tick;
+                    last_tick = input.tick;
+                    format!("{}{}", delta, action_code(input.action))
+                })
+                .collect();
+            let _ = writeln!(text, "{}", tokens.join(" "));
+        }
+
+        let checksum = fnv1a(text.as_bytes());
+        let _ = writeln!(text, "checksum {:016x}", checksum);
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate();
+        let mut rules = Rules::default();
+        let mut engine_version = None;
+        let mut player = String::new();
+        let mut seed = None;
+        let mut ticks = None;
+        let mut score = None;
+        let mut line_count = None;
+        let mut inputs = None;
+        // Lines read so far with '\n' endings, so Windows line endings don't break the checksum
+        let mut hashed = String::new();
+
+        while let Some((index, line)) = lines.next() {
+            let line_start = hashed.len();
+            hashed.push_str(line);
+            hashed.push('\n');
+            let error = |message: &str| format!("line {}: {}", index + 1, message);
+            let (keyword, value) = line.split_once(' ').unwrap_or((line, ""));
+
+            match keyword {
+                "format" => {
+                    let version: u32 = value.parse().map_err(|_| error("bad format version"))?;
+                    if version != REPLAY_FORMAT_VERSION {
+                        return Err(format!(
+                            "replay format {} is not supported (expected {})",
+                            version, REPLAY_FORMAT_VERSION
+                        ));
+                    }
+                }
+                "engine" => engine_version = Some(value.to_string()),
+                "player" => player = value.to_string(),
+                "seed" => seed = Some(value.parse().map_err(|_| error("bad seed"))?),
+                "mode" => rules.mode = GameMode::from_name(value).ok_or_else(|| error("bad mode"))?,
+                "start_level" => rules.start_level = value.parse().map_err(|_| error("bad start level"))?,
+                "marathon_goal" => {
+                    rules.marathon_goal = parse_marathon_goal(value).ok_or_else(|| error("bad marathon goal"))?
+                }
+                "leveling" => {
+                    rules.leveling = Leveling::from_name(value).ok_or_else(|| error("bad leveling"))?
+                }
+                "width" => rules.width = value.parse().map_err(|_| error("bad width"))?,
+                "height" => rules.height = value.parse().map_err(|_| error("bad height"))?,
+                "lock_mode" => {
+                    rules.lock_mode = LockMode::from_name(value).ok_or_else(|| error("bad lock mode"))?
+                }
+                "preview_count" => {
+                    rules.preview_count = value.parse().map_err(|_| error("bad preview count"))?
+                }
+                "randomizer" => {
+                    rules.randomizer =
+                        RandomizerKind::from_name(value).ok_or_else(|| error("bad randomizer"))?
+                }
+                "pieces" => {
+                    let count: usize = value.parse().map_err(|_| error("bad piece set length"))?;
+                    let mut source = String::new();
+                    for _ in 0..count {
+                        let (_, piece_line) = lines.next().ok_or_else(|| error("piece set cut short"))?;
+                        source.push_str(piece_line);
+                        source.push('\n');
+                    }
+                    hashed.push_str(&source);
+                    let piece_set = PieceSet::parse(&source).map_err(|err| error(&format!("piece set {}", err)))?;
+                    rules.piece_set = Arc::new(piece_set);
+                }
+                "ticks" => ticks = Some(value.parse().map_err(|_| error("bad tick count"))?),
+                "score" => score = Some(value.parse().map_err(|_| error("bad score"))?),
+                "lines" => line_count = Some(value.parse().map_err(|_| error("bad line count"))?),
+                "inputs" => {
+                    let count: usize = value.parse().map_err(|_| error("bad input count"))?;
+                    // The count is untrusted until the checksum, so check it against the file's size
+                    if count > text.len() / MIN_INPUT_LENGTH {
+                        return Err(error("more inputs announced than the file holds"));
+                    }
+                    let mut parsed = Vec::with_capacity(count);
+                    let mut tick = 0;
+                    while parsed.len() < count {
+                        let (_, input_line) = lines.next().ok_or_else(|| error("inputs cut short"))?;
+                        hashed.push_str(input_line);
+                        hashed.push('\n');
+                        for token in input_line.split_whitespace() {
+                            let input = parse_input(token, tick)
+                                .ok_or_else(|| error(&format!("bad input `{}`", token)))?;
+                            tick = input.tick;
+                            parsed.push(input);
+                        }
+                    }
+                    if parsed.len() != count {
+                        return Err(error("more inputs than announced"));
+                    }
+                    inputs = Some(parsed);
+                }
+                "checksum" => {
+                    let expected = u64::from_str_radix(value, 16).map_err(|_| error("bad checksum"))?;
+                    let actual = fnv1a(&hashed.as_bytes()[..line_start]);
+                    if actual != expected {
+                        return Err("checksum does not match, the file was changed".to_string());
+                    }
+                    let missing = |field: &str| format!("missing `{}`", field);
+                    let file = ReplayFile {
+                        engine_version: engine_version.ok_or_else(|| missing("engine"))?,
+                        player,
+                        replay: Replay {
+                            rules,
+                            seed: seed.ok_or_else(|| missing("seed"))?,
+                            inputs: inputs.ok_or_else(|| missing("inputs"))?,
+                            ticks: ticks.ok_or_else(|| missing("ticks"))?,
+                        },
+                        score: score.ok_or_else(|| missing("score"))?,
+                        lines: line_count.ok_or_else(|| missing("lines"))?,
+                    };
+                    check_length(&file.replay)?;
+                    return Ok(file);
+                }
+                _ => return Err(error(&format!("unknown keyword `{}`", keyword))),
+            }
+        }
+
+        Err("missing `checksum`".to_string())
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|err| format!("{}: {}", path, err))?;
+        Self::parse(&contents)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_text()).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    /// Plays the replay through the engine and checks that it reaches the
+    /// claimed score and line count.
+    pub fn verify(&self) -> Result<(), String> {
+        check_length(&self.replay)?;
+        if self.engine_version != ENGINE_VERSION {
+            return Err(format!(
+                "recorded with engine {}, this is engine {}",
+                self.engine_version, ENGINE_VERSION
+            ));
+        }
+
+        let mut player = ReplayPlayer::new(self.replay.clone());
+        player.run_to_end();
+        let game = player.game();
+        if game.score == u64::MAX {
+            return Err("score overflows".to_string());
+        }
+        if game.score != self.score || game.lines_cleared != self.lines {
+            return Err(format!(
+                "claims {} points and {} lines but plays back to {} points and {} lines",
+                self.score, self.lines, game.score, game.lines_cleared
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Rejects replays too long to play back and inputs past the replay's end.
+fn check_length(replay: &Replay) -> Result<(), String> {
+    if replay.ticks > MAX_REPLAY_TICKS {
+        return Err(format!(
+            "replay is {} ticks long, more than the {} allowed",
+            replay.ticks, MAX_REPLAY_TICKS
+        ));
+    }
+    if replay.inputs.last().is_some_and(|input| input.tick > replay.ticks) {
+        return Err("inputs continue past the end of the replay".to_string());
+    }
+    Ok(())
+}
+
+fn action_code(action: Action) -> char {
+    match action {
+        Action::MoveLeft => 'L',
+        Action::MoveRight => 'R',
+        Action::RotateCw => 'C',
+        Action::RotateCcw => 'A',
+        Action::Rotate180 => 'F',
+        Action::HardDrop => 'D',
+        Action::Hold => 'H',
+        Action::SoftDrop(true) => 'S',
+        Action::SoftDrop(false) => 's',
+    }
+}
+
+/// Reads a `<delta><code>` token made `delta` ticks after `last_tick`.
+fn parse_input(token: &str, last_tick: u64) -> Option<Input> {
+    let code = token.chars().last()?;
+    let delta: u64 = token[..token.len() - code.len_utf8()].parse().ok()?;
+    let action = match code {
+        'L' => Action::MoveLeft,
+        'R' => Action::MoveRight,
+        'C' => Action::RotateCw,
+        'A' => Action::RotateCcw,
+        'F' => Action::Rotate180,
+        'D' => Action::HardDrop,
+        'H' => Action::Hold,
+        'S' => Action::SoftDrop(true),
+        's' => Action::SoftDrop(false),
+        _ => return None,
+    };
+    Some(Input {
+        tick: last_tick.checked_add(delta)?,
+        action,
+    })
+}
+
+/// 64-bit FNV-1a hash.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
@@ -0,0 +1,152 @@
+//! Per-game rule settings that survive a restart.
+
+use crate::constants::*;
+use crate::pieces::PieceSet;
+use crate::randomizer::RandomizerKind;
+use std::sync::Arc;
+
+/// When a grounded piece's lock delay starts over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    /// Moves and rotations on the ground reset the delay, up to `MAX_LOCK_RESETS` times
+    MoveReset,
+    /// Only falling to a new lowest row resets the delay
+    StepReset,
+    /// Every move and rotation on the ground resets the delay
+    Infinite,
+}
+
+impl LockMode {
+    /// Name used in settings and replay files.
+    pub fn name(self) -> &'static str {
+        match self {
+            LockMode::MoveReset => "move",
+            LockMode::StepReset => "step",
+            LockMode::Infinite => "infinite",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "move" => Some(LockMode::MoveReset),
+            "step" => Some(LockMode::StepReset),
+            "infinite" => Some(LockMode::Infinite),
+            _ => None,
+        }
+    }
+}
+
+/// How Marathon levels are earned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Leveling {
+    /// Every `LINES_PER_LEVEL` lines
+    Fixed,
+    /// `GOAL_POINTS_PER_LEVEL` x level goal points, with bigger clears and T-spins worth more
+    Variable,
+}
+
+impl Leveling {
+    /// Name used in settings and replay files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Leveling::Fixed => "fixed",
+            Leveling::Variable => "variable",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fixed" => Some(Leveling::Fixed),
+            "variable" => Some(Leveling::Variable),
+            _ => None,
+        }
+    }
+}
+
+/// What a game is played for and how it ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    /// Level up from `start_level` until reaching `marathon_goal` lines or topping out
+    Marathon,
+    /// Clear `SPRINT_LINES` lines as fast as possible
+    Sprint,
+    /// Score as much as possible in `ULTRA_TICKS`
+    Ultra,
+    /// Play forever at constant gravity; topping out makes room instead of ending the game
+    Zen,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [GameMode::Marathon, GameMode::Sprint, GameMode::Ultra, GameMode::Zen];
+
+    /// Name used in settings and replay files.
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Marathon => "marathon",
+            GameMode::Sprint => "sprint",
+            GameMode::Ultra => "ultra",
+            GameMode::Zen => "zen",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            // Endless play is a Marathon without a goal
+            "marathon" | "endless" => Some(GameMode::Marathon),
+            "sprint" => Some(GameMode::Sprint),
+            "ultra" => Some(GameMode::Ultra),
+            "zen" => Some(GameMode::Zen),
+            _ => None,
+        }
+    }
+}
+
+/// A Marathon goal as written in settings and replay files: its line count, or `endless`.
+pub fn marathon_goal_name(goal: Option<u32>) -> String {
+    goal.map_or("endless".to_string(), |lines| lines.to_string())
+}
+
+/// Reads a Marathon goal written by `marathon_goal_name`.
+pub fn parse_marathon_goal(text: &str) -> Option<Option<u32>> {
+    match text {
+        "endless" => Some(None),
+        _ => text.parse().ok().filter(|&lines| lines > 0).map(Some),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rules {
+    pub mode: GameMode,
+    /// Marathon's first level, 1 to `MAX_START_LEVEL`
+    pub start_level: u32,
+    /// Lines that complete a Marathon, `None` to play until topping out
+    pub marathon_goal: Option<u32>,
+    pub leveling: Leveling,
+    /// Board columns, `MIN_GRID_WIDTH` to `MAX_GRID_WIDTH`
+    pub width: usize,
+    /// Visible board rows, `MIN_GRID_HEIGHT` to `MAX_GRID_HEIGHT`
+    pub height: usize,
+    pub lock_mode: LockMode,
+    /// Pieces shown in the next queue, 1 to `MAX_PREVIEW_COUNT`
+    pub preview_count: usize,
+    pub randomizer: RandomizerKind,
+    /// The pieces dealt, the guideline tetrominoes by default
+    pub piece_set: Arc<PieceSet>,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            mode: GameMode::Marathon,
+            start_level: 1,
+            marathon_goal: None,
+            leveling: Leveling::Fixed,
+            width: DEFAULT_GRID_WIDTH,
+            height: DEFAULT_GRID_HEIGHT,
+            lock_mode: LockMode::MoveReset,
+            preview_count: DEFAULT_PREVIEW_COUNT,
+            randomizer: RandomizerKind::Bag7,
+            piece_set: Arc::new(PieceSet::standard()),
+        }
+    }
+}
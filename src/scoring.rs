@@ -0,0 +1,57 @@
+use crate::constants::*;
+
+/// How the last locked piece was spun into place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TSpin {
+    None,
+    Mini,
+    Full,
+}
+
+/// Clears of four or more lines and line-clearing T-spins keep a back-to-back chain going.
+pub fn is_difficult(lines: u32, t_spin: TSpin) -> bool {
+    lines >= 4 || (lines > 0 && t_spin != TSpin::None)
+}
+
+/// Back-to-back difficult clears are worth 1.5x.
+pub fn back_to_back_bonus(points: u64) -> u64 {
+    points * 3 / 2
+}
+
+/// Base points for a lock, before the level multiplier.
+pub fn line_clear_score(lines: u32, t_spin: TSpin) -> u32 {
+    match (t_spin, lines) {
+        (TSpin::None, 1) => SCORE_SINGLE,
+        (TSpin::None, 2) => SCORE_DOUBLE,
+        (TSpin::None, 3) => SCORE_TRIPLE,
+        (TSpin::None, 4) => SCORE_TETRIS,
+        (TSpin::None, n) if n > 4 => SCORE_TETRIS + (n - 4) * SCORE_EXTRA_LINE,
+        (TSpin::Mini, 0) => SCORE_TSPIN_MINI,
+        (TSpin::Mini, 1) => SCORE_TSPIN_MINI_SINGLE,
+        (TSpin::Mini, 2) => SCORE_TSPIN_MINI_DOUBLE,
+        (TSpin::Full, 0) => SCORE_TSPIN,
+        (TSpin::Full, 1) => SCORE_TSPIN_SINGLE,
+        (TSpin::Full, 2) => SCORE_TSPIN_DOUBLE,
+        (TSpin::Full, 3) => SCORE_TSPIN_TRIPLE,
+        _ => 0,
+    }
+}
+
+/// Goal points a lock earns towards the next level under the variable goal:
+/// one per 100 base points, so a single is worth 1, a tetris 8 and a T-spin
+/// double 12.
+pub fn goal_points(lines: u32, t_spin: TSpin) -> u32 {
+    line_clear_score(lines, t_spin) / SCORE_SINGLE
+}
+
+/// Bonus for emptying the whole board, before the level multiplier.
+pub fn perfect_clear_bonus(lines: u32, back_to_back: bool) -> u32 {
+    match lines {
+        1 => SCORE_PERFECT_CLEAR_SINGLE,
+        2 => SCORE_PERFECT_CLEAR_DOUBLE,
+        3 => SCORE_PERFECT_CLEAR_TRIPLE,
+        4.. if back_to_back => SCORE_PERFECT_CLEAR_B2B_TETRIS,
+        4.. => SCORE_PERFECT_CLEAR_TETRIS,
+        _ => 0,
+    }
+}
@@ -0,0 +1,93 @@
+//! Wall kick tables, including the Super Rotation System data.
+//!
+//! Offsets are listed as published in the guideline, with +y pointing up.
+//! The grid grows downward, so callers must subtract the y offset.
+
+pub type Kick = (i32, i32);
+
+// Rows follow `TRANSITIONS`: 0->R, R->0, R->2, 2->R, 2->L, L->2, L->0, 0->L
+const JLSTZ_KICKS: [[Kick; 5]; 8] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+];
+
+const I_KICKS: [[Kick; 5]; 8] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+];
+
+// 180 kicks from the SRS+ rule set, shared by JLSTZ and I.
+// Rows follow `HALF_TURNS`: 0->2, 2->0, R->L, L->R
+const HALF_TURN_KICKS: [[Kick; 6]; 4] = [
+    [(0, 0), (0, 1), (1, 1), (-1, 1), (1, 0), (-1, 0)],
+    [(0, 0), (0, -1), (-1, -1), (1, -1), (-1, 0), (1, 0)],
+    [(0, 0), (1, 0), (1, 2), (1, 1), (0, 2), (0, 1)],
+    [(0, 0), (-1, 0), (-1, 2), (-1, 1), (0, 2), (0, 1)],
+];
+
+// Tried when a table has no entry for a transition: rotate in place only
+const NO_KICKS: [Kick; 1] = [(0, 0)];
+
+// (from, to) rotation states of each row of the tables above
+const TRANSITIONS: [(u8, u8); 8] = [(0, 1), (1, 0), (1, 2), (2, 1), (2, 3), (3, 2), (3, 0), (0, 3)];
+const HALF_TURNS: [(u8, u8); 4] = [(0, 2), (2, 0), (1, 3), (3, 1)];
+
+/// Kick offsets for every pair of rotation states.
+#[derive(Debug, Clone, Default)]
+pub struct KickTable {
+    kicks: [[Vec<Kick>; 4]; 4],
+}
+
+impl KickTable {
+    /// No kicks at all: the piece only rotates in place.
+    pub fn none() -> Self {
+        KickTable::default()
+    }
+
+    /// SRS kicks for the J, L, S, T and Z pieces.
+    pub fn jlstz() -> Self {
+        Self::from_srs(&JLSTZ_KICKS)
+    }
+
+    /// SRS kicks for the I piece.
+    pub fn i() -> Self {
+        Self::from_srs(&I_KICKS)
+    }
+
+    fn from_srs(quarter_turns: &[[Kick; 5]; 8]) -> Self {
+        let mut table = KickTable::default();
+        for (&(from, to), kicks) in TRANSITIONS.iter().zip(quarter_turns) {
+            table.set(from, to, kicks.to_vec());
+        }
+        for (&(from, to), kicks) in HALF_TURNS.iter().zip(&HALF_TURN_KICKS) {
+            table.set(from, to, kicks.to_vec());
+        }
+        table
+    }
+
+    pub fn set(&mut self, from: u8, to: u8, kicks: Vec<Kick>) {
+        self.kicks[from as usize % 4][to as usize % 4] = kicks;
+    }
+
+    /// Kick offsets to try, in order, when rotating from one rotation state to another.
+    pub fn offsets(&self, from: u8, to: u8) -> &[Kick] {
+        let kicks = &self.kicks[from as usize % 4][to as usize % 4];
+        if kicks.is_empty() {
+            &NO_KICKS
+        } else {
+            kicks
+        }
+    }
+}
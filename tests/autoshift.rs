@@ -0,0 +1,110 @@
+use tetris::autoshift::{AutoShift, Direction, Shift};
+use tetris::constants::TICK_DURATION;
+use tetris::game::{Cell, GameState, State};
+use tetris::replay::Action;
+use tetris::rules::Rules;
+use tetris::tetromino::Tetromino;
+
+// Powers of two keep the timers exact
+const DAS: f32 = 1.0;
+const ARR: f32 = 0.5;
+
+fn shift(direction: Direction, cells: u32) -> Option<Shift> {
+    Some(Shift { direction, cells })
+}
+
+/// Holds right, pressed on the first call, for `calls` updates of `delta_time`.
+fn hold_right(auto_shift: &mut AutoShift, calls: usize, delta_time: f32) -> Vec<Option<Shift>> {
+    (0..calls)
+        .map(|call| auto_shift.update(false, true, false, call == 0, delta_time))
+        .collect()
+}
+
+#[test]
+fn first_shift_comes_exactly_when_das_is_charged() {
+    let mut auto_shift = AutoShift::new(DAS, ARR);
+    let shifts = hold_right(&mut auto_shift, 6, 0.25);
+    assert_eq!(shifts[0], shift(Direction::Right, 1));
+    assert_eq!(shifts[1..4], [None, None, None]);
+    // Held for 1.0 after the press
+    assert_eq!(shifts[4], shift(Direction::Right, 1));
+    assert_eq!(shifts[5], None);
+}
+
+#[test]
+fn repeat_timer_carries_over() {
+    let mut auto_shift = AutoShift::new(DAS, ARR);
+    assert_eq!(hold_right(&mut auto_shift, 1, 0.0)[0], shift(Direction::Right, 1));
+
+    // Charged 0.75 ago: the first shift plus one repeat, 0.25 left over
+    assert_eq!(auto_shift.update(false, true, false, false, 1.75), shift(Direction::Right, 2));
+    // The leftover 0.25 counts towards the next repeat
+    assert_eq!(auto_shift.update(false, true, false, false, 0.25), shift(Direction::Right, 1));
+    assert_eq!(auto_shift.update(false, true, false, false, 0.25), None);
+    // Several repeats in one long update
+    assert_eq!(auto_shift.update(false, true, false, false, 1.25), shift(Direction::Right, 3));
+}
+
+#[test]
+fn zero_arr_goes_straight_to_the_wall() {
+    let mut auto_shift = AutoShift::new(DAS, 0.0);
+    let shifts = hold_right(&mut auto_shift, 6, 0.25);
+    assert_eq!(shifts[..4], [shift(Direction::Right, 1), None, None, None]);
+    assert_eq!(shifts[4], shift(Direction::Right, u32::MAX));
+    assert_eq!(shifts[5], shift(Direction::Right, u32::MAX));
+}
+
+#[test]
+fn last_pressed_direction_wins_until_released() {
+    let mut auto_shift = AutoShift::new(DAS, ARR);
+    assert_eq!(auto_shift.update(true, false, true, false, 0.0), shift(Direction::Left, 1));
+    assert_eq!(auto_shift.update(true, false, false, false, 0.5), None);
+
+    // Pressing right while left is held takes over with a fresh charge
+    assert_eq!(auto_shift.update(true, true, false, true, 0.0), shift(Direction::Right, 1));
+    assert_eq!(auto_shift.update(true, true, false, false, 0.75), None);
+    assert_eq!(auto_shift.update(true, true, false, false, 0.25), shift(Direction::Right, 1));
+
+    // Releasing right falls back to the still held left, charging from scratch
+    assert_eq!(auto_shift.update(true, false, false, false, 0.5), None);
+    assert_eq!(auto_shift.update(true, false, false, false, 0.5), shift(Direction::Left, 1));
+
+    // Releasing both stops
+    assert_eq!(auto_shift.update(false, false, false, false, 1.0), None);
+}
+
+#[test]
+fn charge_is_kept_through_the_line_clear_animation() {
+    let mut game = GameState::with_seed(Rules::default(), 1);
+    let bottom = game.total_height() - 1;
+    for x in (0..3).chain(7..game.width()) {
+        game.grid[bottom][x] = Some(Cell::Garbage);
+    }
+    let set = game.rules.piece_set.clone();
+    let id = set.find("I").unwrap();
+    game.current_piece = Tetromino::new(id, set.get(id), game.width());
+    game.apply(Action::HardDrop);
+    assert_eq!(game.state, State::LineClearAnimation);
+
+    // Right is pressed and held while the clear plays; the engine ignores the moves
+    let das = 10.0 * TICK_DURATION;
+    let mut auto_shift = AutoShift::new(das, 0.0);
+    let mut ticks = 0;
+    while game.state == State::LineClearAnimation {
+        let shift = auto_shift.update(false, true, false, ticks == 0, TICK_DURATION);
+        if shift.is_some() {
+            assert!(!game.apply(Action::MoveRight));
+        }
+        game.tick();
+        ticks += 1;
+    }
+    assert!(ticks as f32 * TICK_DURATION > das);
+
+    // The new piece goes to the wall on its first tick, fully charged
+    let shift = auto_shift.update(false, true, false, false, TICK_DURATION);
+    assert_eq!(shift, Some(Shift { direction: Direction::Right, cells: u32::MAX }));
+    assert!(game.apply(Action::MoveRight));
+    while game.apply(Action::MoveRight) {}
+    let right_edge = game.current_piece.get_blocks().iter().map(|&(x, _)| x).max();
+    assert_eq!(right_edge, Some(game.width() as i32 - 1));
+}
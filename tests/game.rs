@@ -0,0 +1,172 @@
+use tetris::constants::{BUFFER_HEIGHT, MAX_LOCK_RESETS};
+use tetris::game::{Cell, GameState, State, TopOut};
+use tetris::replay::Action;
+use tetris::rules::{LockMode, Rules};
+use tetris::tetromino::Tetromino;
+
+/// A T resting on the floor, as if it had fallen there.
+fn grounded_t(lock_mode: LockMode) -> GameState {
+    let rules = Rules {
+        lock_mode,
+        ..Rules::default()
+    };
+    let mut game = GameState::with_seed(rules, 1);
+    let set = game.rules.piece_set.clone();
+    let id = set.find("T").unwrap();
+    game.current_piece = Tetromino::new(id, set.get(id), game.width());
+    game.current_piece.y = 38;
+    game.lowest_row = 38;
+    game
+}
+
+/// Slides the piece back and forth, each move well inside the lock delay,
+/// and returns how many moves it took before the piece locked.
+fn moves_until_lock(game: &mut GameState, max_moves: u32) -> Option<u32> {
+    for moves in 0..=max_moves {
+        for _ in 0..18 {
+            game.tick();
+        }
+        if !game.is_board_empty() {
+            return Some(moves);
+        }
+        if moves % 2 == 0 {
+            game.move_left();
+        } else {
+            game.move_right();
+        }
+    }
+    None
+}
+
+#[test]
+fn move_reset_locks_after_15_resets() {
+    let mut game = grounded_t(LockMode::MoveReset);
+    // Each of the first 15 moves restarts the lock delay; the 16th doesn't
+    // and the piece locks on the next tick
+    assert_eq!(moves_until_lock(&mut game, 40), Some(MAX_LOCK_RESETS + 1));
+}
+
+#[test]
+fn infinite_lock_never_runs_out_of_resets() {
+    let mut game = grounded_t(LockMode::Infinite);
+    assert_eq!(moves_until_lock(&mut game, 40), None);
+}
+
+#[test]
+fn step_reset_ignores_moves() {
+    let mut game = grounded_t(LockMode::StepReset);
+    // The lock delay of 30 ticks runs out during the second wait
+    assert_eq!(moves_until_lock(&mut game, 40), Some(1));
+}
+
+#[test]
+fn pause_freezes_every_timer() {
+    let mut game = GameState::with_seed(Rules::default(), 1);
+    for _ in 0..10 {
+        game.tick();
+    }
+    let (y, fall_progress) = (game.current_piece.y, game.fall_progress);
+
+    game.pause();
+    assert!(game.is_paused());
+    assert!(!game.apply(Action::MoveLeft));
+    for _ in 0..1000 {
+        game.tick();
+    }
+    assert_eq!((game.ticks, game.current_piece.y, game.fall_progress), (10, y, fall_progress));
+
+    game.resume();
+    assert_eq!(game.state, State::Playing);
+    assert!(game.apply(Action::MoveLeft));
+}
+
+#[test]
+fn stacking_in_the_middle_tops_out() {
+    let mut game = GameState::with_seed(Rules::default(), 1);
+    for _ in 0..200 {
+        if game.game_over {
+            break;
+        }
+        game.hard_drop();
+    }
+    assert!(game.game_over);
+    // The last piece lands wholly in the vanish zone
+    assert_eq!(game.top_out, Some(TopOut::LockOut));
+}
+
+fn fill_row(game: &mut GameState, y: usize, gaps: &[usize]) {
+    for x in 0..game.width() {
+        game.grid[y][x] = (!gaps.contains(&x)).then_some(Cell::Garbage);
+    }
+}
+
+/// Makes `name` the falling piece, turned `turns` quarter turns clockwise,
+/// with its box at column `x` of the top row.
+fn set_piece(game: &mut GameState, name: &str, turns: u8, x: i32) {
+    let set = game.rules.piece_set.clone();
+    let id = set.find(name).unwrap();
+    let mut piece = Tetromino::new(id, set.get(id), game.width());
+    for _ in 0..turns {
+        piece.rotate_cw(set.get(id));
+    }
+    piece.x = x;
+    piece.y = 0;
+    game.current_piece = piece;
+}
+
+#[test]
+fn locking_above_the_visible_field_locks_out() {
+    let mut game = GameState::with_seed(Rules::default(), 1);
+    // A stack up to the top of the visible field, with no full rows
+    for y in BUFFER_HEIGHT..game.total_height() {
+        fill_row(&mut game, y, &[0]);
+    }
+    set_piece(&mut game, "O", 0, 4);
+    game.hard_drop();
+    assert!(game.game_over);
+    assert_eq!(game.top_out, Some(TopOut::LockOut));
+}
+
+#[test]
+fn spawning_into_the_stack_blocks_out() {
+    let mut game = GameState::with_seed(Rules::default(), 1);
+    // Blocks left in the spawn area, with room to lock lower down
+    fill_row(&mut game, BUFFER_HEIGHT - 1, &[0, 1, 2, 7, 8, 9]);
+    set_piece(&mut game, "O", 0, 0);
+    game.hard_drop();
+    assert!(game.game_over);
+    assert_eq!(game.top_out, Some(TopOut::BlockOut));
+}
+
+#[test]
+fn clearing_the_spawn_row_does_not_block_out() {
+    let mut game = GameState::with_seed(Rules::default(), 1);
+    // Every piece spawns into row 19. A vertical I in the right column
+    // completes it and reaches down into the visible field.
+    for y in BUFFER_HEIGHT + 1..game.total_height() {
+        fill_row(&mut game, y, &[0]);
+    }
+    fill_row(&mut game, BUFFER_HEIGHT, &[0, 9]);
+    fill_row(&mut game, BUFFER_HEIGHT - 1, &[9]);
+    set_piece(&mut game, "I", 1, 7);
+    game.hard_drop();
+    assert_eq!(game.lines_being_cleared, vec![BUFFER_HEIGHT - 1]);
+    assert!(!game.game_over);
+
+    while game.state == State::LineClearAnimation {
+        game.tick();
+    }
+    assert!(!game.game_over);
+    assert_eq!(game.lines_cleared, 1);
+    assert!(game.apply(Action::MoveLeft));
+}
+
+#[test]
+fn garbage_pushing_blocks_off_the_top_tops_out() {
+    let mut game = GameState::with_seed(Rules::default(), 1);
+    game.add_garbage(19, 0);
+    assert!(!game.game_over);
+    game.add_garbage(game.total_height(), 0);
+    assert!(game.game_over);
+    assert_eq!(game.top_out, Some(TopOut::GarbageOut));
+}
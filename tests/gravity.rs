@@ -0,0 +1,44 @@
+use tetris::constants::{MAX_GRAVITY, SOFT_DROP_FACTOR};
+use tetris::gravity::{rows_per_frame, soft_drop_rows_per_frame};
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 0.005, "{} != {}", actual, expected);
+}
+
+#[test]
+fn curve_matches_the_guideline_formula() {
+    // One second per row
+    assert_close(rows_per_frame(1), 1.0 / 60.0);
+    // 0.793 s per row
+    assert_close(rows_per_frame(2), 1.0 / (0.793 * 60.0));
+    // 0.702 ^ 14 s per row
+    assert_close(rows_per_frame(15), 2.36);
+    assert_close(rows_per_frame(18), 11.44);
+}
+
+#[test]
+fn level_0_falls_like_level_1() {
+    assert_eq!(rows_per_frame(0), rows_per_frame(1));
+}
+
+#[test]
+fn curve_only_gets_faster() {
+    for level in 1..200 {
+        assert!(rows_per_frame(level + 1) >= rows_per_frame(level), "level {}", level);
+    }
+}
+
+#[test]
+fn capped_at_20g_from_level_19() {
+    assert!(rows_per_frame(18) < MAX_GRAVITY);
+    // Where the formula's base goes to zero or below, and far past it
+    for level in [19, 20, 50, 115, 200, u32::MAX] {
+        assert_eq!(rows_per_frame(level), MAX_GRAVITY, "level {}", level);
+    }
+}
+
+#[test]
+fn soft_drop_is_faster_up_to_20g() {
+    assert_close(soft_drop_rows_per_frame(1), SOFT_DROP_FACTOR / 60.0);
+    assert_eq!(soft_drop_rows_per_frame(15), MAX_GRAVITY);
+}
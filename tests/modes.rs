@@ -0,0 +1,197 @@
+use tetris::constants::{SPRINT_LINES, ULTRA_TICKS};
+use tetris::game::{Cell, GameState, State};
+use tetris::replay::{Action, ReplayPlayer};
+use tetris::replay_file::ReplayFile;
+use tetris::rules::{GameMode, Leveling, Rules};
+use tetris::tetromino::Tetromino;
+
+fn game(mode: GameMode) -> GameState {
+    let rules = Rules {
+        mode,
+        ..Rules::default()
+    };
+    GameState::with_seed(rules, 3)
+}
+
+/// Fills the bottom four rows but for the right column and drops a
+/// vertical I into it. The clear animation is left running.
+fn drop_tetris(game: &mut GameState) {
+    let bottom = game.total_height() - 1;
+    for y in bottom - 3..=bottom {
+        for x in 0..game.width() - 1 {
+            game.grid[y][x] = Some(Cell::Garbage);
+        }
+    }
+    let set = game.rules.piece_set.clone();
+    let id = set.find("I").unwrap();
+    let mut piece = Tetromino::new(id, set.get(id), game.width());
+    piece.rotate_cw(set.get(id));
+    piece.x = game.width() as i32 - 3;
+    game.current_piece = piece;
+    game.apply(Action::HardDrop);
+}
+
+fn finish_clear(game: &mut GameState) {
+    while game.state == State::LineClearAnimation {
+        game.tick();
+    }
+}
+
+#[test]
+fn sprint_finishes_at_40_lines_with_splits() {
+    let mut game = game(GameMode::Sprint);
+    for _ in 0..SPRINT_LINES / 4 {
+        assert!(!game.is_over());
+        for _ in 0..20 {
+            game.tick();
+        }
+        drop_tetris(&mut game);
+        finish_clear(&mut game);
+    }
+    assert!(game.finished && !game.game_over);
+    assert_eq!(game.lines_cleared, SPRINT_LINES);
+    assert_eq!(game.level, 1);
+
+    // A split every 10 lines, timed at the lock that crossed it
+    assert_eq!(game.splits.len(), 4);
+    assert!(game.splits.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(game.splits[3], game.ticks);
+
+    // The clock stops
+    let ticks = game.ticks;
+    game.tick();
+    assert_eq!(game.ticks, ticks);
+    assert!(!game.apply(Action::MoveLeft));
+}
+
+#[test]
+fn ultra_ends_after_two_minutes_and_scores_the_last_clear() {
+    let mut game = game(GameMode::Ultra);
+    while game.ticks < ULTRA_TICKS - 5 {
+        game.tick();
+    }
+    assert!(!game.is_over());
+
+    drop_tetris(&mut game);
+    assert_eq!(game.state, State::LineClearAnimation);
+    let score = game.score;
+    for _ in 0..100 {
+        game.tick();
+    }
+    assert_eq!(game.ticks, ULTRA_TICKS);
+    assert!(game.finished && !game.game_over);
+    assert_eq!(game.lines_cleared, 4);
+    assert!(game.score > score);
+}
+
+#[test]
+fn ultra_replays_to_the_same_end() {
+    let mut game = game(GameMode::Ultra);
+    // Gravity alone stacks in the middle slowly enough to last two minutes
+    game.apply(Action::Hold);
+    for step in 0..2 * ULTRA_TICKS {
+        if step % 600 == 300 {
+            game.apply(Action::MoveLeft);
+        }
+        game.tick();
+    }
+    assert!(game.finished);
+
+    let mut player = ReplayPlayer::new(game.replay());
+    player.run_to_end();
+    assert!(player.game().finished);
+    assert_eq!(player.game().ticks, ULTRA_TICKS);
+    assert_eq!(player.game().grid, game.grid);
+}
+
+#[test]
+fn marathon_finishes_at_its_goal_from_the_start_level() {
+    let rules = Rules {
+        mode: GameMode::Marathon,
+        start_level: 5,
+        marathon_goal: Some(150),
+        ..Rules::default()
+    };
+    let mut game = GameState::with_seed(rules, 9);
+    assert_eq!(game.level, 5);
+    assert_eq!(game.level_goal_remaining(), Some(10));
+
+    // Skip ahead to four lines short of the goal
+    game.lines_cleared = 146;
+    drop_tetris(&mut game);
+    assert!(game.finished && !game.game_over);
+    assert_eq!(game.lines_cleared, 150);
+    assert_eq!(game.level, 5 + 15);
+
+    // The start level and goal travel with the replay
+    let text = ReplayFile::from_game(&game, "Anh").to_text();
+    let rules = ReplayFile::parse(&text).unwrap().replay.rules;
+    assert_eq!((rules.start_level, rules.marathon_goal), (5, Some(150)));
+}
+
+#[test]
+fn endless_marathon_keeps_going() {
+    let mut game = game(GameMode::Marathon);
+    assert_eq!(game.rules.marathon_goal, None);
+    game.lines_cleared = 996;
+    drop_tetris(&mut game);
+    finish_clear(&mut game);
+    assert!(!game.is_over());
+    assert_eq!(game.level, 101);
+}
+
+#[test]
+fn variable_goal_levels_on_goal_points() {
+    let rules = Rules {
+        leveling: Leveling::Variable,
+        ..Rules::default()
+    };
+    let mut game = GameState::with_seed(rules, 9);
+    assert_eq!(game.level_goal_remaining(), Some(5));
+
+    // A tetris is worth 8 goal points: level 1 takes 5, the other 3 carry
+    // over towards the 10 level 2 needs
+    drop_tetris(&mut game);
+    finish_clear(&mut game);
+    assert_eq!((game.level, game.goal_points), (2, 3));
+    assert_eq!(game.level_goal_remaining(), Some(7));
+}
+
+#[test]
+fn fixed_goal_counts_lines() {
+    let mut game = game(GameMode::Marathon);
+    drop_tetris(&mut game);
+    finish_clear(&mut game);
+    assert_eq!((game.level, game.level_goal_remaining()), (1, Some(6)));
+}
+
+#[test]
+fn zen_makes_room_instead_of_topping_out() {
+    let mut game = game(GameMode::Zen);
+    // Fill the whole matrix, vanish zone included
+    game.add_garbage(game.total_height(), 0);
+    assert!(!game.is_over());
+    assert!(!game.is_board_empty());
+    // The piece fits again, so it can still move
+    assert!(game.apply(Action::HardDrop));
+    assert!(!game.is_over());
+}
+
+#[test]
+fn zen_plays_forever_at_level_1() {
+    let mut game = game(GameMode::Zen);
+    // Stacking in the middle would top out in a few dozen pieces
+    for _ in 0..500 {
+        game.apply(Action::HardDrop);
+        for _ in 0..25 {
+            game.tick();
+        }
+    }
+    assert!(!game.is_over());
+    assert_eq!(game.level, 1);
+    assert_eq!(game.level_goal_remaining(), None);
+
+    let mut player = ReplayPlayer::new(game.replay());
+    player.run_to_end();
+    assert_eq!(player.game().grid, game.grid);
+}
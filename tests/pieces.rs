@@ -0,0 +1,78 @@
+use std::fs;
+use tetris::pieces::PieceSet;
+use tetris::randomizer::{PieceQueue, RandomizerKind};
+
+#[test]
+fn shipped_sets_parse() {
+    for (path, count) in [
+        ("pieces/standard.txt", 7),
+        ("pieces/trominoes.txt", 2),
+        ("pieces/pentominoes.txt", 18),
+    ] {
+        let set = PieceSet::load(path).unwrap();
+        assert_eq!(set.len(), count, "{}", path);
+    }
+}
+
+#[test]
+fn shipped_sets_mix_into_one_file() {
+    let mixed: String = ["pieces/standard.txt", "pieces/trominoes.txt", "pieces/pentominoes.txt"]
+        .iter()
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let set = PieceSet::parse(&mixed).unwrap();
+    assert_eq!(set.len(), 7 + 2 + 18);
+    for name in ["I", "T", "I3", "I5", "L5'", "T5"] {
+        assert!(set.find(name).is_some(), "{}", name);
+    }
+
+    // One bag deals every piece of the mix
+    let mut queue = PieceQueue::new(RandomizerKind::Bag7, &set, 3);
+    let mut dealt: Vec<_> = set.ids().map(|_| queue.next()).collect();
+    dealt.sort();
+    assert_eq!(dealt, set.ids().collect::<Vec<_>>());
+}
+
+#[test]
+fn single_shape_is_turned_for_the_other_states() {
+    let set = PieceSet::parse("set Test\npiece J\nshape\nX..\nXXX\n...\n").unwrap();
+    assert_eq!(set.name, "Test");
+    let piece = set.get(set.find("J").unwrap());
+    assert_eq!(piece.rotations[1], vec![vec![0, 1, 1], vec![0, 1, 0], vec![0, 1, 0]]);
+    assert_eq!(piece.cell_count(), 4);
+}
+
+#[test]
+fn parse_errors_name_the_line() {
+    for (text, message) in [
+        ("", "no pieces defined"),
+        ("# only a comment\n", "no pieces defined"),
+        ("shape\nX\n", "line 1: expected `piece <name>` first"),
+        ("piece\n", "line 1: piece needs a name"),
+        ("piece A\ncolor 1 1\nshape\nX\n", "line 2: color needs three values"),
+        ("piece A\ncolor red\nshape\nX\n", "line 2: bad color"),
+        ("piece A\nspawn 1\nshape\nX\n", "line 2: spawn needs two values"),
+        ("piece A\nspawn 0 1000\nshape\nX\n", "line 2: spawn offset out of range"),
+        ("piece A\nkicks sideways\nshape\nX\n", "line 2: kicks must be srs, srs_i or none"),
+        ("piece A\nkick 0 Q 1,2\nshape\nX\n", "line 2: kick needs two states"),
+        ("piece A\nkick 0 R 1;2\nshape\nX\n", "line 2: kick offsets are written x,y"),
+        ("piece A\nkick 0 R 1,999\nshape\nX\n", "line 2: kick offsets are written x,y"),
+        ("piece A\nwobble 3\nshape\nX\n", "line 2: unknown keyword `wobble`"),
+        ("piece A\n", "piece `A` (line 1): needs one shape or four"),
+        ("piece A\nshape\n..\n..\n", "piece `A` (line 1): shape has no cells"),
+        ("piece A\nshape\nXX\nX\n", "shape rows must all be the same length"),
+        ("piece A\nshape\nXX\nX.\nX.\n", "a single shape must be square"),
+        ("piece A\nshape\nX\nshape\nX\n", "needs one shape or four"),
+        ("piece A\nshape\nX\n\npiece A\nshape\nX\n", "piece `A` is defined twice"),
+    ] {
+        let err = PieceSet::parse(text).unwrap_err();
+        assert!(err.contains(message), "{:?}: {}", text, err);
+    }
+}
+
+#[test]
+fn load_errors_name_the_file() {
+    let err = PieceSet::load("pieces/missing.txt").unwrap_err();
+    assert!(err.starts_with("pieces/missing.txt: "), "{}", err);
+}
@@ -0,0 +1,73 @@
+use tetris::game::GameState;
+use tetris::pieces::{PieceId, PieceSet};
+use tetris::randomizer::{PieceQueue, RandomizerKind};
+use tetris::rules::Rules;
+
+const KINDS: [RandomizerKind; 5] = [
+    RandomizerKind::Bag7,
+    RandomizerKind::Bag14,
+    RandomizerKind::Memoryless,
+    RandomizerKind::Nes,
+    RandomizerKind::Tgm,
+];
+
+fn deal(kind: RandomizerKind, seed: u64, count: usize) -> Vec<PieceId> {
+    let mut queue = PieceQueue::new(kind, &PieceSet::standard(), seed);
+    (0..count).map(|_| queue.next()).collect()
+}
+
+#[test]
+fn same_seed_deals_the_same_sequence() {
+    for kind in KINDS {
+        assert_eq!(deal(kind, 42, 500), deal(kind, 42, 500), "{}", kind.name());
+        assert_ne!(deal(kind, 42, 500), deal(kind, 43, 500), "{}", kind.name());
+    }
+}
+
+#[test]
+fn same_seed_starts_games_alike() {
+    for kind in KINDS {
+        let rules = Rules {
+            randomizer: kind,
+            ..Rules::default()
+        };
+        let a = GameState::with_seed(rules.clone(), 7);
+        let b = GameState::with_seed(rules, 7);
+        assert_eq!(a.current_piece.id, b.current_piece.id, "{}", kind.name());
+        assert_eq!(a.next_pieces, b.next_pieces, "{}", kind.name());
+    }
+}
+
+#[test]
+fn peeking_does_not_change_the_sequence() {
+    for kind in KINDS {
+        let mut queue = PieceQueue::new(kind, &PieceSet::standard(), 42);
+        let mut dealt = Vec::new();
+        for round in 0..100 {
+            queue.preview(round % 6 + 1);
+            queue.peek();
+            dealt.push(queue.next());
+        }
+        assert_eq!(dealt, deal(kind, 42, 100), "{}", kind.name());
+    }
+}
+
+#[test]
+fn bags_hold_each_piece_once_or_twice() {
+    for (kind, bag_size) in [(RandomizerKind::Bag7, 7), (RandomizerKind::Bag14, 14)] {
+        for bag in deal(kind, 9, bag_size * 20).chunks(bag_size) {
+            let mut bag = bag.to_vec();
+            bag.sort();
+            let full: Vec<PieceId> = (0..7).flat_map(|id| vec![id; bag_size / 7]).collect();
+            assert_eq!(bag, full, "{}", kind.name());
+        }
+    }
+}
+
+#[test]
+fn names_round_trip() {
+    for kind in KINDS {
+        assert_eq!(RandomizerKind::from_name(kind.name()), Some(kind));
+    }
+    assert_eq!(RandomizerKind::from_name("bag8"), None);
+}
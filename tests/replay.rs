@@ -0,0 +1,67 @@
+use tetris::game::GameState;
+use tetris::replay::{Action, ReplayPlayer};
+use tetris::replay_file::ReplayFile;
+use tetris::rules::Rules;
+
+const ACTIONS: [Action; 10] = [
+    Action::MoveLeft,
+    Action::RotateCw,
+    Action::HardDrop,
+    Action::Hold,
+    Action::MoveRight,
+    Action::MoveRight,
+    Action::SoftDrop(true),
+    Action::Rotate180,
+    Action::SoftDrop(false),
+    Action::HardDrop,
+];
+
+/// Plays `ticks` ticks with an action every few of them.
+fn play(game: &mut GameState, ticks: u64) {
+    for step in 0..ticks {
+        if step % 7 == 0 {
+            game.apply(ACTIONS[(step / 7) as usize % ACTIONS.len()]);
+        }
+        game.tick();
+    }
+}
+
+#[test]
+fn replay_plays_back_to_the_same_game() {
+    let mut game = GameState::with_seed(Rules::default(), 9);
+    play(&mut game, 20_000);
+
+    let mut player = ReplayPlayer::new(game.replay());
+    player.run_to_end();
+    assert_eq!(player.game().score, game.score);
+    assert_eq!(player.game().grid, game.grid);
+    assert_eq!(player.game().game_over, game.game_over);
+
+    // Seeking back replays from the start
+    player.seek(100);
+    assert_eq!(player.tick(), 100);
+    player.run_to_end();
+    assert_eq!(player.game().score, game.score);
+}
+
+#[test]
+fn saved_game_resumes_exactly() {
+    let mut game = GameState::with_seed(Rules::default(), 77);
+    play(&mut game, 300);
+
+    let text = ReplayFile::from_game(&game, "Anh").to_text();
+    let mut player = ReplayPlayer::new(ReplayFile::parse(&text).unwrap().replay);
+    player.run_to_end();
+    let mut resumed = player.into_game();
+    assert_eq!(resumed.grid, game.grid);
+    assert_eq!(resumed.next_pieces, game.next_pieces);
+    assert_eq!(resumed.held_piece, game.held_piece);
+    assert_eq!(resumed.current_piece.get_blocks(), game.current_piece.get_blocks());
+    assert_eq!((resumed.fall_progress, resumed.lock_timer), (game.fall_progress, game.lock_timer));
+
+    // Both carry on alike
+    play(&mut game, 3000);
+    play(&mut resumed, 3000);
+    assert_eq!(resumed.grid, game.grid);
+    assert_eq!(resumed.score, game.score);
+}
@@ -0,0 +1,150 @@
+use tetris::game::GameState;
+use tetris::replay::Action;
+use tetris::replay_file::{ReplayFile, MAX_REPLAY_TICKS};
+use tetris::rules::{GameMode, Rules};
+
+/// A short game with moves, rotations, holds, soft drops and hard drops.
+fn recorded_game() -> GameState {
+    let mut game = GameState::with_seed(Rules::default(), 42);
+    let actions = [
+        Action::MoveLeft,
+        Action::RotateCw,
+        Action::HardDrop,
+        Action::Hold,
+        Action::MoveRight,
+        Action::MoveRight,
+        Action::SoftDrop(true),
+    ];
+    for round in 0..12 {
+        for &action in &actions {
+            game.apply(action);
+            for _ in 0..round % 4 + 1 {
+                game.tick();
+            }
+        }
+        game.apply(Action::SoftDrop(false));
+        game.apply(Action::HardDrop);
+        game.tick();
+    }
+    assert!(game.inputs.len() > 50);
+    game
+}
+
+#[test]
+fn round_trip_verifies() {
+    let game = recorded_game();
+    let text = ReplayFile::from_game(&game, "Anh").to_text();
+
+    let file = ReplayFile::parse(&text).unwrap();
+    assert_eq!(file.player, "Anh");
+    assert_eq!(file.replay.seed, 42);
+    assert_eq!(file.replay.inputs, game.inputs);
+    assert_eq!(file.replay.ticks, game.ticks);
+    assert_eq!((file.score, file.lines), (game.score, game.lines_cleared));
+    file.verify().unwrap();
+}
+
+#[test]
+fn round_trip_tolerates_windows_line_endings() {
+    let text = ReplayFile::from_game(&recorded_game(), "Anh").to_text();
+    let file = ReplayFile::parse(&text.replace('\n', "\r\n")).unwrap();
+    file.verify().unwrap();
+}
+
+#[test]
+fn changed_byte_fails_checksum() {
+    let text = ReplayFile::from_game(&recorded_game(), "Anh").to_text();
+    let tampered = text.replacen("seed 42", "seed 43", 1);
+    assert_ne!(tampered, text);
+    let err = ReplayFile::parse(&tampered).unwrap_err();
+    assert!(err.contains("checksum"), "{}", err);
+}
+
+#[test]
+fn wrong_score_claim_fails_verify() {
+    let game = recorded_game();
+    let mut file = ReplayFile::from_game(&game, "Anh");
+    file.score += 100;
+    // Rewriting the file gives it a valid checksum, so only playback can catch the claim
+    let file = ReplayFile::parse(&file.to_text()).unwrap();
+    let err = file.verify().unwrap_err();
+    assert!(err.contains("plays back to"), "{}", err);
+}
+
+#[test]
+fn bad_input_token_is_an_error() {
+    let text = ReplayFile::from_game(&recorded_game(), "Anh").to_text();
+    let (head, tail) = text.split_once("\ninputs ").unwrap();
+    let (count_line, rest) = tail.split_once('\n').unwrap();
+    let broken = format!("{}\ninputs {}\n3Q {}", head, count_line, rest);
+    let err = ReplayFile::parse(&broken).unwrap_err();
+    assert!(err.contains("bad input `3Q`"), "{}", err);
+}
+
+#[test]
+fn short_input_list_is_an_error() {
+    let game = recorded_game();
+    let text = ReplayFile::from_game(&game, "Anh").to_text();
+    let announced = format!("inputs {}", game.inputs.len());
+    let inflated = text.replace(&announced, &format!("inputs {}", game.inputs.len() + 3));
+    assert!(ReplayFile::parse(&inflated).is_err());
+
+    // Cut off straight after the input count
+    let cut = &text[..text.find(&announced).unwrap() + announced.len()];
+    assert!(ReplayFile::parse(cut).is_err());
+}
+
+#[test]
+fn huge_input_count_is_an_error() {
+    let game = recorded_game();
+    let text = ReplayFile::from_game(&game, "Anh").to_text();
+    let announced = format!("inputs {}", game.inputs.len());
+    let huge = text.replace(&announced, "inputs 4000000000000000000");
+    let err = ReplayFile::parse(&huge).unwrap_err();
+    assert!(err.contains("more inputs announced"), "{}", err);
+}
+
+#[test]
+fn overlong_replay_is_rejected() {
+    let rules = Rules {
+        mode: GameMode::Zen,
+        ..Rules::default()
+    };
+    let mut file = ReplayFile::from_game(&GameState::with_seed(rules, 1), "Anh");
+    file.replay.ticks = u64::MAX;
+    assert!(file.verify().is_err());
+    assert!(ReplayFile::parse(&file.to_text()).is_err());
+
+    file.replay.ticks = MAX_REPLAY_TICKS + 1;
+    assert!(ReplayFile::parse(&file.to_text()).is_err());
+}
+
+#[test]
+fn inputs_past_the_end_are_rejected() {
+    let game = recorded_game();
+    let mut file = ReplayFile::from_game(&game, "Anh");
+    file.replay.ticks = game.inputs.last().unwrap().tick - 1;
+    let err = ReplayFile::parse(&file.to_text()).unwrap_err();
+    assert!(err.contains("past the end"), "{}", err);
+}
+
+#[test]
+fn garbage_never_panics() {
+    let text = ReplayFile::from_game(&recorded_game(), "Anh").to_text();
+    // Every prefix of a valid file, and a few hostile lines
+    for end in (0..text.len()).step_by(7) {
+        let _ = ReplayFile::parse(&text[..end]);
+    }
+    for hostile in [
+        "format 1\npieces 99999999999\n",
+        "format 1\ninputs 18446744073709551615\n",
+        "format 1\ninputs 2\n18446744073709551615L 1L\n",
+        "format 1\nwidth -1\n",
+        "format 1\nstart_level 99999999999\n",
+        "format 1\npieces 3\npiece X\nspawn 2147483647 0\nshape\n",
+        "",
+        "\u{0}\u{0}\u{0}",
+    ] {
+        assert!(ReplayFile::parse(hostile).is_err(), "{:?}", hostile);
+    }
+}
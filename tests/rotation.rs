@@ -0,0 +1,84 @@
+use tetris::game::GameState;
+use tetris::pieces::PieceSet;
+use tetris::tetromino::Tetromino;
+
+/// Makes `name` the falling piece with its box at column `x` and row `y`.
+fn set_piece(game: &mut GameState, name: &str, x: i32, y: i32) {
+    let set = game.rules.piece_set.clone();
+    let id = set.find(name).unwrap();
+    let mut piece = Tetromino::new(id, set.get(id), game.width());
+    piece.x = x;
+    piece.y = y;
+    game.current_piece = piece;
+}
+
+#[test]
+fn four_turns_come_back_to_the_start() {
+    for id in PieceSet::standard().ids() {
+        let name = PieceSet::standard().get(id).name.clone();
+        let mut game = GameState::new();
+        set_piece(&mut game, &name, 3, 25);
+        let start = game.current_piece.get_blocks();
+        for _ in 0..4 {
+            assert!(game.rotate_cw());
+        }
+        assert_eq!(game.current_piece.get_blocks(), start, "{}", name);
+        for _ in 0..4 {
+            assert!(game.rotate_ccw());
+        }
+        assert_eq!(game.current_piece.get_blocks(), start, "{}", name);
+    }
+}
+
+#[test]
+fn i_kicks_off_the_left_wall() {
+    let mut game = GameState::new();
+    set_piece(&mut game, "I", 3, 25);
+    // Vertical in L, its cells in column 1 of the box, flush with the wall
+    assert!(game.rotate_ccw());
+    while game.move_left() {}
+    assert_eq!(game.current_piece.x, -1);
+
+    // Flat in place would poke through the wall; the first I kick for
+    // L->0 moves it one column right
+    assert!(game.rotate_cw());
+    assert_eq!(game.current_piece.rotation, 0);
+    assert_eq!(game.last_rotation_kick, Some((1, 0)));
+    assert!(game.current_piece.get_blocks().iter().all(|&(x, _)| x >= 0));
+}
+
+#[test]
+fn half_turn_matches_two_quarter_turns_in_the_open() {
+    let mut half = GameState::new();
+    set_piece(&mut half, "T", 3, 25);
+    let mut quarters = GameState::new();
+    set_piece(&mut quarters, "T", 3, 25);
+
+    assert!(half.rotate_180());
+    assert!(quarters.rotate_cw());
+    assert!(quarters.rotate_cw());
+    assert_eq!(half.current_piece.rotation, 2);
+    assert_eq!(half.current_piece.get_blocks(), quarters.current_piece.get_blocks());
+}
+
+#[test]
+fn half_turn_uses_its_own_kicks() {
+    // A T flat on the floor, pointing up
+    let mut half = GameState::new();
+    set_piece(&mut half, "T", 3, 38);
+    let mut quarters = GameState::new();
+    set_piece(&mut quarters, "T", 3, 38);
+
+    // Pointing down in place would go through the floor; the 180 kicks
+    // straight up a row
+    assert!(half.rotate_180());
+    assert_eq!(half.last_rotation_kick, Some((0, 1)));
+    assert_eq!((half.current_piece.x, half.current_piece.y), (3, 37));
+
+    // Two quarter turns take the SRS kicks of each step instead and end
+    // up a column to the left
+    assert!(quarters.rotate_cw());
+    assert!(quarters.rotate_cw());
+    assert_eq!(quarters.current_piece.rotation, 2);
+    assert_eq!((quarters.current_piece.x, quarters.current_piece.y), (2, 37));
+}
@@ -0,0 +1,196 @@
+use tetris::game::{Cell, GameState, State};
+use tetris::scoring::TSpin;
+use tetris::tetromino::Tetromino;
+
+/// Makes `name` the falling piece, turned `turns` quarter turns clockwise
+/// with its box at column `x` and row `y`.
+fn set_piece(game: &mut GameState, name: &str, turns: u8, x: i32, y: i32) {
+    let set = game.rules.piece_set.clone();
+    let id = set.find(name).unwrap();
+    let mut piece = Tetromino::new(id, set.get(id), game.width());
+    for _ in 0..turns {
+        piece.rotate_cw(set.get(id));
+    }
+    piece.x = x;
+    piece.y = y;
+    game.current_piece = piece;
+}
+
+/// Locks `name` where it lands in column `x`, without drop points, and
+/// lets any line clear finish. Returns the points the lock scored.
+fn lock_at(game: &mut GameState, name: &str, turns: u8, x: i32) -> u64 {
+    set_piece(game, name, turns, x, 0);
+    game.current_piece.y = game.calculate_ghost_y();
+    let before = game.score;
+    game.hard_drop();
+    while game.state == State::LineClearAnimation {
+        game.tick();
+    }
+    game.score - before
+}
+
+fn fill_row(game: &mut GameState, y: usize, gaps: &[usize]) {
+    for x in 0..game.width() {
+        game.grid[y][x] = (!gaps.contains(&x)).then_some(Cell::Garbage);
+    }
+}
+
+/// A T-spin double slot: the bottom row open at column 4, the row above
+/// open at columns 3 to 5, and an overhang at column 3 two rows up.
+fn tsd_slot(game: &mut GameState, overhang: bool) {
+    fill_row(game, 39, &[4]);
+    fill_row(game, 38, &[3, 4, 5]);
+    if overhang {
+        game.grid[37][3] = Some(Cell::Garbage);
+    }
+}
+
+/// Spins a T from R into the slot and locks it in place.
+fn spin_t_into_slot(game: &mut GameState) -> u64 {
+    set_piece(game, "T", 1, 3, 37);
+    assert!(game.rotate_cw());
+    assert_eq!(game.current_piece.rotation, 2);
+    let before = game.score;
+    game.hard_drop();
+    while game.state == State::LineClearAnimation {
+        game.tick();
+    }
+    game.score - before
+}
+
+#[test]
+fn t_spin_double_is_full_and_scores_1200() {
+    let mut game = GameState::new();
+    tsd_slot(&mut game, true);
+    assert_eq!(spin_t_into_slot(&mut game), 1200);
+    assert_eq!(game.t_spin, TSpin::Full);
+    assert_eq!(game.lines_cleared, 2);
+    assert_eq!(game.back_to_back, 1);
+}
+
+#[test]
+fn t_spin_with_one_front_corner_is_mini() {
+    let mut game = GameState::new();
+    // Three corners filled, but only one on the side the T points to
+    game.grid[37][3] = Some(Cell::Garbage);
+    game.grid[37][5] = Some(Cell::Garbage);
+    game.grid[39][3] = Some(Cell::Garbage);
+    assert_eq!(spin_t_into_slot(&mut game), 100);
+    assert_eq!(game.t_spin, TSpin::Mini);
+    assert_eq!(game.lines_cleared, 0);
+}
+
+#[test]
+fn t_spin_needs_three_corners() {
+    let mut game = GameState::new();
+    tsd_slot(&mut game, false);
+    // Something left over so the double isn't a perfect clear
+    game.grid[37][9] = Some(Cell::Garbage);
+    assert_eq!(spin_t_into_slot(&mut game), 300);
+    assert_eq!(game.t_spin, TSpin::None);
+    assert_eq!(game.back_to_back, 0);
+}
+
+#[test]
+fn t_spin_needs_the_last_move_to_be_a_rotation() {
+    let mut game = GameState::new();
+    tsd_slot(&mut game, true);
+    set_piece(&mut game, "T", 2, 3, 30);
+    // Dropped straight in, not spun
+    game.hard_drop();
+    assert_eq!(game.t_spin, TSpin::None);
+}
+
+#[test]
+fn back_to_back_tetrises_score_half_again() {
+    let mut game = GameState::new();
+    // A block under the stack keeps the tetrises from being perfect clears
+    fill_row(&mut game, 39, &(0..9).collect::<Vec<_>>());
+    for y in 35..39 {
+        fill_row(&mut game, y, &[9]);
+    }
+    // Vertical I: its cells are in column 2 of the box
+    assert_eq!(lock_at(&mut game, "I", 1, 7), 800);
+    assert_eq!(game.back_to_back, 1);
+
+    for y in 35..39 {
+        fill_row(&mut game, y, &[9]);
+    }
+    // 800 x 1.5, plus 50 for the second clear in a row
+    assert_eq!(lock_at(&mut game, "I", 1, 7), 1200 + 50);
+    assert_eq!(game.back_to_back, 2);
+
+    // A single breaks the chain
+    fill_row(&mut game, 39, &[0, 1, 2, 3]);
+    game.grid[38][9] = Some(Cell::Garbage);
+    lock_at(&mut game, "I", 0, 0);
+    assert_eq!(game.back_to_back, 0);
+}
+
+#[test]
+fn combo_adds_50_per_step_until_a_lock_clears_nothing() {
+    let mut game = GameState::new();
+    let single = |game: &mut GameState| {
+        fill_row(game, 39, &[0, 1, 2, 3]);
+        game.grid[38][9] = Some(Cell::Garbage);
+        // Horizontal I: its cells are in row 1 of the box
+        lock_at(game, "I", 0, 0)
+    };
+
+    assert_eq!(single(&mut game), 100);
+    assert_eq!(single(&mut game), 150);
+    assert_eq!(single(&mut game), 200);
+    assert_eq!(game.combo, 2);
+
+    // An O on the far side clears nothing
+    assert_eq!(lock_at(&mut game, "O", 0, 5), 0);
+    assert_eq!(game.combo, -1);
+    assert_eq!(single(&mut game), 100);
+}
+
+#[test]
+fn level_multiplies_clear_points() {
+    let mut game = GameState::new();
+    game.level = 3;
+    fill_row(&mut game, 39, &[0, 1, 2, 3]);
+    game.grid[38][9] = Some(Cell::Garbage);
+    assert_eq!(lock_at(&mut game, "I", 0, 0), 300);
+}
+
+#[test]
+fn perfect_clear_single_adds_800() {
+    let mut game = GameState::new();
+    fill_row(&mut game, 39, &[3, 4, 5, 6]);
+    assert_eq!(lock_at(&mut game, "I", 0, 3), 100 + 800);
+    assert_eq!(game.perfect_clears, 1);
+    assert!(game.is_board_empty());
+}
+
+#[test]
+fn perfect_clear_tetris_bonus_grows_back_to_back() {
+    let mut game = GameState::new();
+    for y in 36..40 {
+        fill_row(&mut game, y, &[9]);
+    }
+    assert_eq!(lock_at(&mut game, "I", 1, 7), 800 + 2000);
+
+    for y in 36..40 {
+        fill_row(&mut game, y, &[9]);
+    }
+    assert_eq!(lock_at(&mut game, "I", 1, 7), 1200 + 3200 + 50);
+    assert_eq!(game.perfect_clears, 2);
+}
+
+#[test]
+fn score_saturates_instead_of_overflowing() {
+    let mut game = GameState::new();
+    game.level = u32::MAX;
+    game.score = u64::MAX - 1000;
+    for y in 36..40 {
+        fill_row(&mut game, y, &[9]);
+    }
+    game.grid[35][0] = Some(Cell::Garbage);
+    lock_at(&mut game, "I", 1, 7);
+    assert_eq!(game.lines_cleared, 4);
+    assert_eq!(game.score, u64::MAX);
+}